@@ -1,15 +1,74 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse::Parser, parse_macro_input, ItemStruct};
+use syn::{
+    parse::{Parse, ParseStream, Parser},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, ItemStruct, Path, Token,
+};
+
+struct ComponentArg {
+    name: Ident,
+    value: Option<Path>,
+}
+
+impl Parse for ComponentArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        let value = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(ComponentArg { name, value })
+    }
+}
+
+// #[component(clone, serde, rollback, hooks, on_add = path, on_remove = path, world = path)]
+#[derive(Default)]
+struct ComponentArgs {
+    clone: bool,
+    // `ComponentHooks` is implemented by hand, e.g. next to the world it needs
+    hooks: bool,
+    serde: bool,
+    rollback: bool,
+    on_add: Option<Path>,
+    on_remove: Option<Path>,
+    world: Option<Path>,
+}
+
+impl Parse for ComponentArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = ComponentArgs::default();
+        for arg in Punctuated::<ComponentArg, Token![,]>::parse_terminated(input)? {
+            match (arg.name.to_string().as_str(), arg.value) {
+                ("clone", None) => args.clone = true,
+                ("hooks", None) => args.hooks = true,
+                ("serde", None) => args.serde = true,
+                ("rollback", None) => args.rollback = true,
+                ("on_add", Some(value)) => args.on_add = Some(value),
+                ("on_remove", Some(value)) => args.on_remove = Some(value),
+                ("world", Some(value)) => args.world = Some(value),
+                _ => return Err(syn::Error::new(arg.name.span(), "unknown component argument")),
+            }
+        }
+        Ok(args)
+    }
+}
 
 #[proc_macro_attribute]
-pub fn component(_: TokenStream, input: TokenStream) -> TokenStream {
+pub fn component(attr: TokenStream, input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as ComponentArgs);
     let mut item_struct = parse_macro_input!(input as ItemStruct);
+    let reflect = match reflect_component(&mut item_struct) {
+        Ok(reflect) => reflect,
+        Err(err) => return err.to_compile_error().into(),
+    };
     let ident = &item_struct.ident;
 
     if let syn::Fields::Named(ref mut fields) = item_struct.fields {
         fields.named.insert(0,
-            syn::Field::parse_named.parse2(quote!{pub entity: usize})
+            syn::Field::parse_named.parse2(quote!{pub entity: ecs::Entity})
             .unwrap(),
         );
         fields.named.insert(1,
@@ -18,15 +77,100 @@ pub fn component(_: TokenStream, input: TokenStream) -> TokenStream {
         );
     }
 
+    // hooks take the concrete world, `World` unless the struct names another one
+    let hooks = if args.hooks {
+        quote!{}
+    } else if args.on_add.is_none() && args.on_remove.is_none() {
+        quote!{
+            impl<W> ecs::ComponentHooks<W> for #ident {}
+        }
+    } else {
+        let world = args.world.map_or(quote!{World}, |world| quote!{#world});
+        let on_add = args.on_add.map(|hook| quote!{
+            fn on_add(world: &mut #world, entity: ecs::Entity) {
+                #hook(world, entity)
+            }
+        });
+        let on_remove = args.on_remove.map(|hook| quote!{
+            fn on_remove(world: &mut #world, entity: ecs::Entity) {
+                #hook(world, entity)
+            }
+        });
+        quote!{
+            impl ecs::ComponentHooks<#world> for #ident {
+                #on_add
+                #on_remove
+            }
+        }
+    };
+
+    if args.clone || args.serde || args.rollback {
+        item_struct.attrs.push(syn::parse_quote!(#[derive(Clone)]));
+    }
+
+    // only opted in components are copied by `clone_entity` and prefabs
+    let clone = if args.clone {
+        quote!{
+            impl ecs::ComponentClone for #ident {
+                fn clone_component(&self) -> Option<Self> {
+                    Some(self.clone())
+                }
+            }
+        }
+    } else {
+        quote!{
+            impl ecs::ComponentClone for #ident {}
+        }
+    };
+
+    let rollback = if args.rollback {
+        quote!{
+            impl ecs::Rollback for #ident {
+                fn snapshot(storage: &ecs::SparseSet<Self>) -> Option<ecs::SparseSet<Self>> {
+                    Some(storage.clone())
+                }
+            }
+        }
+    } else {
+        quote!{
+            impl ecs::Rollback for #ident {}
+        }
+    };
+
+    let save = if args.serde {
+        item_struct.attrs.push(syn::parse_quote!(#[derive(ecs::serde::Serialize, ecs::serde::Deserialize)]));
+        item_struct.attrs.push(syn::parse_quote!(#[serde(crate = "ecs::serde")]));
+        let map_entities = item_struct.fields.iter().filter_map(map_entity_field);
+        quote!{
+            impl ecs::SaveComponent for #ident {
+                type Saved = Self;
+
+                fn save(&self) -> Option<Self> {
+                    Some(self.clone())
+                }
+
+                fn load(saved: Self) -> Option<Self> {
+                    Some(saved)
+                }
+
+                fn map_entities(&mut self, map: &ecs::EntityMap) {
+                    #(#map_entities)*
+                }
+            }
+        }
+    } else {
+        quote!{
+            impl ecs::SaveComponent for #ident {
+                type Saved = ();
+            }
+        }
+    };
+
     return quote!{
         #item_struct
 
         impl Component for #ident {
-            fn get_id() -> usize {
-                core::any::TypeId::of::<Self> as usize
-            }
-
-            fn entity(&self) -> usize {
+            fn entity(&self) -> ecs::Entity {
                 self.entity
             }
 
@@ -34,5 +178,183 @@ pub fn component(_: TokenStream, input: TokenStream) -> TokenStream {
                 self.one_frame
             }
         }
+
+        #hooks
+
+        #clone
+
+        #save
+
+        #rollback
+
+        #reflect
+    }.into()
+}
+
+// field types that have an `ecs::Value` representation
+const REFLECT_TYPES: &[&str] = &[
+    "bool", "i32", "u32", "usize", "f32", "f64", "String", "Entity", "Vector2f", "Vector3f", "Vector3d", "Color", "UnitQuaternion",
+];
+
+// field options: #[reflect(skip)], #[reflect(read_only)], #[reflect(setter = method)]
+fn reflect_component(item_struct: &mut ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &item_struct.ident;
+    let mut infos = Vec::new();
+    let mut getters = Vec::new();
+    let mut setters = Vec::new();
+
+    for field in item_struct.fields.iter_mut() {
+        let mut skip = false;
+        let mut read_only = false;
+        let mut setter = None;
+        for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("reflect")) {
+            for arg in attr.parse_args_with(Punctuated::<ComponentArg, Token![,]>::parse_terminated)? {
+                match (arg.name.to_string().as_str(), arg.value) {
+                    ("skip", None) => skip = true,
+                    ("read_only", None) => read_only = true,
+                    ("setter", Some(value)) => setter = Some(value),
+                    _ => return Err(syn::Error::new(arg.name.span(), "unknown reflect argument")),
+                }
+            }
+        }
+        field.attrs.retain(|attr| !attr.path.is_ident("reflect"));
+
+        let name = match &field.ident {
+            Some(name) if !skip => name,
+            _ => continue,
+        };
+        let ty = &field.ty;
+        let type_name = quote!(#ty).to_string().replace(' ', "");
+        let readable = match ty {
+            syn::Type::Path(path) => path.path.segments.last().is_some_and(|segment| {
+                segment.arguments.is_empty() && REFLECT_TYPES.contains(&segment.ident.to_string().as_str())
+            }),
+            _ => false,
+        };
+        let editable = readable && !read_only;
+        let field_name = name.to_string();
+
+        infos.push(quote!{
+            ecs::FieldInfo {
+                name: #field_name,
+                type_name: #type_name,
+                editable: #editable,
+            }
+        });
+        if readable {
+            getters.push(quote!{
+                #field_name => Some(ecs::ReflectValue::to_value(&self.#name)),
+            });
+        }
+        if editable {
+            let set = match setter {
+                Some(setter) => quote!{ self.#setter(value) },
+                None => quote!{ self.#name = value },
+            };
+            setters.push(quote!{
+                #field_name => match ecs::ReflectValue::from_value(value) {
+                    Some(value) => {
+                        #set;
+                        true
+                    }
+                    None => false,
+                },
+            });
+        }
+    }
+
+    Ok(quote!{
+        impl ecs::Reflect for #ident {
+            fn type_name(&self) -> &'static str {
+                stringify!(#ident)
+            }
+
+            fn fields(&self) -> &'static [ecs::FieldInfo] {
+                const FIELDS: &[ecs::FieldInfo] = &[#(#infos),*];
+                FIELDS
+            }
+
+            fn field(&self, name: &str) -> Option<ecs::Value> {
+                match name {
+                    #(#getters)*
+                    _ => None,
+                }
+            }
+
+            #[allow(unused_variables)]
+            fn set_field(&mut self, name: &str, value: ecs::Value) -> bool {
+                match name {
+                    #(#setters)*
+                    _ => false,
+                }
+            }
+        }
+    })
+}
+
+// remaps fields typed `Entity`, `Option<Entity>` or `Vec<Entity>`, the component's own entity is set on insert
+fn map_entity_field(field: &syn::Field) -> Option<proc_macro2::TokenStream> {
+    let name = field.ident.as_ref()?;
+    if name == "entity" {
+        return None;
+    }
+
+    let segment = match &field.ty {
+        syn::Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident == "Entity" {
+        return Some(quote!{ self.#name = map.map(self.#name); });
+    }
+
+    let inner = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(syn::Type::Path(path)) => path.path.segments.last()?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if inner.ident != "Entity" {
+        return None;
+    }
+
+    if segment.ident == "Option" {
+        Some(quote!{ self.#name = self.#name.map(|entity| map.map(entity)); })
+    } else if segment.ident == "Vec" {
+        Some(quote!{
+            for entity in self.#name.iter_mut() {
+                *entity = map.map(*entity);
+            }
+        })
+    } else {
+        None
+    }
+}
+
+// inserts every field as a component or nested bundle on the same entity
+#[proc_macro_attribute]
+pub fn bundle(_: TokenStream, input: TokenStream) -> TokenStream {
+    let item_struct = parse_macro_input!(input as ItemStruct);
+    let ident = &item_struct.ident;
+
+    let fields = match &item_struct.fields {
+        syn::Fields::Named(fields) => &fields.named,
+        _ => {
+            return syn::Error::new_spanned(&item_struct, "bundles need named fields")
+                .to_compile_error()
+                .into()
+        }
+    };
+    let names = fields.iter().map(|field| &field.ident);
+    let types = fields.iter().map(|field| &field.ty);
+
+    quote!{
+        #item_struct
+
+        impl<W> ecs::Bundle<W> for #ident where #(#types: ecs::Bundle<W>),* {
+            fn insert(self, world: &mut W, entity: ecs::Entity) {
+                #(ecs::Bundle::<W>::insert(self.#names, world, entity);)*
+            }
+        }
     }.into()
 }
@@ -0,0 +1,38 @@
+use std::cell::RefCell;
+
+type Command<W> = Box<dyn FnOnce(&mut W)>;
+
+// structural changes recorded through a shared borrow, applied later with exclusive access
+pub struct CommandQueue<W> {
+    commands: RefCell<Vec<Command<W>>>,
+}
+
+impl<W> CommandQueue<W> {
+    pub fn new() -> Self {
+        Self {
+            commands: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn push(&self, command: impl FnOnce(&mut W) + 'static) {
+        self.commands.borrow_mut().push(Box::new(command));
+    }
+
+    pub fn take(&self) -> Vec<Command<W>> {
+        std::mem::take(&mut *self.commands.borrow_mut())
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.borrow().is_empty()
+    }
+}
+
+impl<W> Default for CommandQueue<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    any::{Any, TypeId},
+    cell::UnsafeCell,
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+use crate::{Access, ComponentTicks, Entity, EntityStorage, Fetch, ReadOnlyFetch, SparseSet};
+
+trait AnyStorage {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn remove(&mut self, entity: Entity) -> bool;
+    fn contains(&self, entity: Entity) -> bool;
+    fn entities(&self) -> &[Entity];
+    fn retain(&mut self, keep: &dyn Fn(Entity) -> bool);
+}
+
+// the cell lets queries borrow several storages mutably through a shared `DynamicComponents`
+struct Storage<T>(UnsafeCell<SparseSet<T>>);
+
+impl<T> Storage<T> {
+    fn get(&self) -> &SparseSet<T> {
+        // mutable access either goes through `&mut self` or through a query that borrows the world mutably
+        unsafe { &*self.0.get() }
+    }
+}
+
+impl<T: 'static> AnyStorage for Storage<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn remove(&mut self, entity: Entity) -> bool {
+        self.0.get_mut().remove(entity).is_some()
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.get().contains(entity)
+    }
+
+    fn entities(&self) -> &[Entity] {
+        self.get().entities()
+    }
+
+    fn retain(&mut self, keep: &dyn Fn(Entity) -> bool) {
+        self.0.get_mut().retain(keep);
+    }
+}
+
+// storages of components registered at runtime, e.g. by plugin crates
+#[derive(Default)]
+pub struct DynamicComponents {
+    storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+}
+
+impl DynamicComponents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<T: 'static>(&mut self) {
+        self.storages
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Storage(UnsafeCell::new(SparseSet::<T>::new()))));
+    }
+
+    pub fn is_registered<T: 'static>(&self) -> bool {
+        self.storages.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn storage<T: 'static>(&self) -> Option<&SparseSet<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any().downcast_ref::<Storage<T>>())
+            .map(|storage| storage.get())
+    }
+
+    pub fn storage_mut<T: 'static>(&mut self) -> Option<&mut SparseSet<T>> {
+        self.storages
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any_mut().downcast_mut::<Storage<T>>())
+            .map(|storage| storage.0.get_mut())
+    }
+
+    fn storage_ptr<T: 'static>(&self) -> Option<*mut SparseSet<T>> {
+        self.storages
+            .get(&TypeId::of::<T>())
+            .and_then(|storage| storage.as_any().downcast_ref::<Storage<T>>())
+            .map(|storage| storage.0.get())
+    }
+
+    pub fn contains(&self, type_id: TypeId, entity: Entity) -> bool {
+        self.storages
+            .get(&type_id)
+            .is_some_and(|storage| storage.contains(entity))
+    }
+
+    pub fn entities(&self, type_id: TypeId) -> &[Entity] {
+        self.storages
+            .get(&type_id)
+            .map_or(&[], |storage| storage.entities())
+    }
+
+    pub fn remove_entity(&mut self, entity: Entity) {
+        for storage in self.storages.values_mut() {
+            storage.remove(entity);
+        }
+    }
+
+    pub fn retain(&mut self, keep: impl Fn(Entity) -> bool) {
+        for storage in self.storages.values_mut() {
+            storage.retain(&keep);
+        }
+    }
+
+    // number of components over all storages
+    pub fn len(&self) -> usize {
+        self.storages.values().map(|storage| storage.entities().len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub trait DynamicStorage {
+    /// # Safety
+    /// `world` must point to a live world.
+    unsafe fn dynamic_ptr(world: *mut Self) -> *const DynamicComponents;
+}
+
+// query terms for runtime components, `Dyn<T>` fetches `&T` and `DynMut<T>` fetches `&mut T`
+pub struct Dyn<T>(PhantomData<fn() -> T>);
+
+pub struct DynMut<T>(PhantomData<fn() -> T>);
+
+unsafe impl<'w, W: DynamicStorage + 'w, T: 'static> Fetch<'w, W> for Dyn<T> {
+    type Item = &'w T;
+    type State = Option<&'w SparseSet<T>>;
+
+    fn access(access: &mut Access) {
+        access.read::<T>();
+    }
+
+    unsafe fn init(world: *mut W) -> Self::State {
+        (*W::dynamic_ptr(world)).storage_ptr::<T>().map(|storage| &*storage)
+    }
+
+    // an unregistered component matches nothing
+    fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+        Some(state.map_or(&[], |storage| storage.entities()))
+    }
+
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        state.and_then(|storage| storage.get(entity))
+    }
+}
+
+unsafe impl<T> ReadOnlyFetch for Dyn<T> {}
+
+unsafe impl<'w, W: DynamicStorage + EntityStorage + 'w, T: 'static> Fetch<'w, W> for DynMut<T> {
+    type Item = &'w mut T;
+    type State = Option<(&'w SparseSet<T>, *mut T, *mut ComponentTicks, u32)>;
+
+    fn access(access: &mut Access) {
+        access.write::<T>();
+    }
+
+    unsafe fn init(world: *mut W) -> Self::State {
+        let storage = (*W::dynamic_ptr(world)).storage_ptr::<T>()?;
+        let data = (*storage).data_ptr();
+        let ticks = (*storage).ticks_ptr();
+        Some((&*storage, data, ticks, W::change_tick(world)))
+    }
+
+    fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+        Some(state.as_ref().map_or(&[], |state| state.0.entities()))
+    }
+
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        let state = state.as_mut()?;
+        let dense = state.0.dense_index(entity)?;
+        (*state.2.add(dense)).changed = state.3;
+        Some(&mut *state.1.add(dense))
+    }
+}
@@ -0,0 +1,178 @@
+use std::{cell::Cell, rc::Rc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::SparseSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    // never alive, stands in until a builder or bundle assigns the real entity
+    pub const PLACEHOLDER: Entity = Entity {
+        index: u32::MAX,
+        generation: u32::MAX,
+    };
+
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl Default for Entity {
+    fn default() -> Self {
+        Self::PLACEHOLDER
+    }
+}
+
+// hands out fresh indices without borrowing the allocator, shared by detached command queues
+#[derive(Clone)]
+pub struct EntityReserver {
+    next: Rc<Cell<u32>>,
+}
+
+impl EntityReserver {
+    pub fn reserve(&self) -> Entity {
+        let index = self.next.get();
+        self.next.set(index + 1);
+        Entity {
+            index,
+            generation: 0,
+        }
+    }
+}
+
+pub struct Entities {
+    generations: Vec<u32>,
+    alive: SparseSet<()>,
+    free: Vec<u32>,
+    // indices below `next` are taken, those past `generations` come alive on `flush`
+    reserver: EntityReserver,
+}
+
+impl Entities {
+    pub fn new() -> Self {
+        Self {
+            generations: Vec::new(),
+            alive: SparseSet::new(),
+            free: Vec::new(),
+            reserver: EntityReserver {
+                next: Rc::new(Cell::new(0)),
+            },
+        }
+    }
+
+    pub fn create(&mut self) -> Entity {
+        self.flush();
+        let entity = match self.free.pop() {
+            Some(index) => Entity {
+                index,
+                generation: self.generations[index as usize],
+            },
+            None => {
+                let entity = self.reserver.reserve();
+                self.generations.push(0);
+                entity
+            }
+        };
+        self.alive.insert(entity, ());
+        entity
+    }
+
+    // hands out a fresh index through a shared borrow, the entity comes alive on `flush`
+    pub fn reserve(&self) -> Entity {
+        self.reserver.reserve()
+    }
+
+    pub fn reserver(&self) -> EntityReserver {
+        self.reserver.clone()
+    }
+
+    // takes over the state of `other` but keeps handing out indices through the same reserver.
+    // Reservations not flushed yet stay taken, the indices below them are free again
+    pub fn restore(&mut self, other: &Entities) {
+        let pending = self.generations.len() as u32..self.reserver.next.get();
+        self.generations = other.generations.clone();
+        self.alive = other.alive.clone();
+        self.free = other.free.clone();
+        self.reserver.next.set(other.reserver.next.get());
+        if pending.is_empty() || pending.end <= self.reserver.next.get() {
+            return;
+        }
+
+        self.flush();
+        for index in self.generations.len() as u32..pending.start {
+            self.generations.push(0);
+            self.free.push(index);
+        }
+        self.reserver.next.set(pending.end);
+    }
+
+    pub fn flush(&mut self) {
+        while (self.generations.len() as u32) < self.reserver.next.get() {
+            self.generations.push(0);
+            self.alive.insert(
+                Entity {
+                    index: self.generations.len() as u32 - 1,
+                    generation: 0,
+                },
+                (),
+            );
+        }
+    }
+
+    // bumps the generation so every handle to the old entity goes stale
+    pub fn destroy(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        let index = entity.index();
+        self.alive.remove(entity);
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free.push(entity.index);
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.alive.contains(entity)
+    }
+
+    pub fn alive(&self) -> &[Entity] {
+        self.alive.entities()
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alive.is_empty()
+    }
+}
+
+// the copy gets its own reserver, reservations on one side don't show up on the other
+impl Clone for Entities {
+    fn clone(&self) -> Self {
+        Self {
+            generations: self.generations.clone(),
+            alive: self.alive.clone(),
+            free: self.free.clone(),
+            reserver: EntityReserver {
+                next: Rc::new(Cell::new(self.reserver.next.get())),
+            },
+        }
+    }
+}
+
+impl Default for Entities {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+};
+
+// events stay readable for the frame they were sent in and the one after
+pub struct Events<T> {
+    previous: Vec<T>,
+    current: Vec<T>,
+    previous_start: usize,
+    event_count: usize,
+}
+
+impl<T> Events<T> {
+    pub fn new() -> Self {
+        Self {
+            previous: Vec::new(),
+            current: Vec::new(),
+            previous_start: 0,
+            event_count: 0,
+        }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+        self.event_count += 1;
+    }
+
+    pub fn update(&mut self) {
+        self.previous_start = self.event_count - self.current.len();
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    pub fn clear(&mut self) {
+        self.previous_start = self.event_count;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    // a reader that only sees events sent from now on
+    pub fn reader(&self) -> EventReader<T> {
+        EventReader {
+            last: self.event_count,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn read<'a>(&'a self, reader: &mut EventReader<T>) -> impl Iterator<Item = &'a T> {
+        let skip = reader.last.saturating_sub(self.previous_start);
+        reader.last = self.event_count;
+        self.previous.iter().chain(self.current.iter()).skip(skip)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().chain(self.current.iter())
+    }
+
+    pub fn len(&self) -> usize {
+        self.previous.len() + self.current.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for Events<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct EventReader<T> {
+    last: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for EventReader<T> {
+    fn default() -> Self {
+        Self {
+            last: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+pub struct EventWriter<'a, T> {
+    events: &'a mut Events<T>,
+}
+
+impl<'a, T> EventWriter<'a, T> {
+    pub fn new(events: &'a mut Events<T>) -> Self {
+        Self { events }
+    }
+
+    pub fn send(&mut self, event: T) {
+        self.events.send(event);
+    }
+
+    pub fn send_batch(&mut self, events: impl IntoIterator<Item = T>) {
+        for event in events {
+            self.events.send(event);
+        }
+    }
+}
+
+trait EventQueue {
+    fn update(&mut self);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> EventQueue for Events<T> {
+    fn update(&mut self) {
+        Events::update(self);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct EventChannels {
+    channels: HashMap<TypeId, Box<dyn EventQueue>>,
+}
+
+impl EventChannels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&Events<T>> {
+        self.channels
+            .get(&TypeId::of::<T>())
+            .and_then(|channel| channel.as_any().downcast_ref::<Events<T>>())
+    }
+
+    pub fn get_or_insert<T: 'static>(&mut self) -> &mut Events<T> {
+        self.channels
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Events::<T>::new()))
+            .as_any_mut()
+            .downcast_mut::<Events<T>>()
+            .unwrap()
+    }
+
+    pub fn update(&mut self) {
+        for channel in self.channels.values_mut() {
+            channel.update();
+        }
+    }
+}
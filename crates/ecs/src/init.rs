@@ -1,60 +1,453 @@
 #[macro_export]
 macro_rules! init_ecs {
-    ($($struct_name:ident),*) => {
+    ($($struct_name:ident),* $(,)? $(; tags: $($tag:ident),* $(,)?)?) => {
+        $crate::init_ecs!(@world [$($struct_name => $struct_name,)* Name => $crate::Name] [$($struct_name),*] [$($($tag),*)?]);
+    };
+
+    // tags are zero-sized markers, they only cost the sparse index
+    // `Name` has no `get_name_mut`, renaming goes through a new `Name` so the index sees it
+    (@world [$($struct_name:ident => $struct_ty:ty),*] [$($mut_name:ident),*] [$($tag:ident),*]) => {
 
         use paste::paste;
         use std::collections::HashMap;
 
+        $(
+            const _: () = assert!(std::mem::size_of::<$tag>() == 0, "tags must be zero-sized");
+        )*
+
         paste::paste! {
             pub struct World {
-                $([<$struct_name:lower>]: Vec<$struct_name>,)*
-                entities: HashMap<usize,usize>,
-                one_frame: Vec<(usize, usize)>,
+                $([<$struct_name:lower>]: $crate::SparseSet<$struct_ty>,)*
+                $([<$tag:lower>]: $crate::SparseSet<$tag>,)*
+                entities: $crate::Entities,
+                names: $crate::NameIndex,
+                one_frame: Vec<($crate::Entity, usize)>,
+                dynamic: $crate::DynamicComponents,
+                registry: $crate::ComponentRegistry,
+                resources: $crate::Resources,
+                events: $crate::EventChannels,
+                commands: std::rc::Rc<$crate::CommandQueue<World>>,
+                change_tick: u32,
+                last_change_tick: u32,
+            }
+        }
+
+        paste::paste! {
+            #[derive($crate::serde::Serialize, $crate::serde::Deserialize)]
+            #[serde(crate = "ecs::serde")]
+            pub struct SavedEntity {
+                pub entity: $crate::Entity,
+                $(
+                    #[serde(default, skip_serializing_if = "Option::is_none")]
+                    pub [<$struct_name:lower>]: Option<<$struct_ty as $crate::SaveComponent>::Saved>,
+                )*
+                $(
+                    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+                    pub [<$tag:lower>]: bool,
+                )*
+            }
+
+            impl SavedEntity {
+                fn is_empty(&self) -> bool {
+                    true $(&& self.[<$struct_name:lower>].is_none())* $(&& !self.[<$tag:lower>])*
+                }
             }
         }
 
+        #[derive($crate::serde::Serialize, $crate::serde::Deserialize)]
+        #[serde(crate = "ecs::serde")]
+        pub struct SavedWorld {
+            pub entities: Vec<SavedEntity>,
+        }
+
+        // world state at one point in time, see `World::snapshot`
+        paste::paste! {
+            pub struct Snapshot {
+                $([<$struct_name:lower>]: Option<$crate::SparseSet<$struct_ty>>,)*
+                $([<$tag:lower>]: $crate::SparseSet<$tag>,)*
+                entities: $crate::Entities,
+                names: $crate::NameIndex,
+                one_frame: Vec<($crate::Entity, usize)>,
+            }
+        }
+
+        #[allow(non_camel_case_types, dead_code)]
+        enum ComponentIndex {
+            $($struct_name,)*
+            $($tag,)*
+        }
+
         impl World {
             paste::paste! {
                 pub fn create() -> Self {
+                    let mut registry = $crate::ComponentRegistry::new();
+                    $(registry.register::<$struct_ty>();)*
+                    $(registry.register::<$tag>();)*
+
                     Self {
-                        $([<$struct_name:lower>]: Vec::new(),)*
-                        entities: HashMap::new(),
+                        $([<$struct_name:lower>]: $crate::SparseSet::new(),)*
+                        $([<$tag:lower>]: $crate::SparseSet::new(),)*
+                        entities: $crate::Entities::new(),
+                        names: $crate::NameIndex::new(),
                         one_frame: Vec::new(),
+                        dynamic: $crate::DynamicComponents::new(),
+                        registry,
+                        resources: $crate::Resources::new(),
+                        events: $crate::EventChannels::new(),
+                        commands: std::rc::Rc::new($crate::CommandQueue::new()),
+                        change_tick: 1,
+                        last_change_tick: 0,
                     }
                 }
             }
 
-            pub fn create_entity(&mut self) -> usize {
-                for (k, v) in &self.entities {
-                    if *v == 0 {
-                        return *k
-                    }
+            pub fn create_entity(&mut self) -> $crate::Entity {
+                self.entities.create()
+            }
+
+            pub fn spawn(&mut self) -> EntityBuilder {
+                let entity = self.entities.create();
+                EntityBuilder { world: self, entity }
+            }
+
+            // copies the components that opted into cloning, `None` if the entity is dead
+            pub fn clone_entity(&mut self, entity: $crate::Entity) -> Option<$crate::Entity> {
+                if !self.entities.is_alive(entity) {
+                    return None;
+                }
+                let clone = self.entities.create();
+                paste::paste! {
+                    $(
+                        if let Some(component) = self.[<$struct_name:lower>].get(entity).and_then(|c| $crate::ComponentClone::clone_component(c)) {
+                            $crate::Bundle::<World>::insert(component, self, clone);
+                        }
+                    )*
+                    $(
+                        if self.[<$tag:lower>].contains(entity) {
+                            self.add_tag(clone, $tag::default());
+                        }
+                    )*
                 }
-                let e = self.entities.len();
-                self.entities.insert(e, 0);
-                e
+                Some(clone)
+            }
+
+            // a prefab of the entity's cloneable components
+            pub fn create_prefab(&self, entity: $crate::Entity) -> $crate::Prefab<World> {
+                let mut prefab = $crate::Prefab::new();
+                paste::paste! {
+                    $(
+                        if let Some(component) = self.[<$struct_name:lower>].get(entity).and_then(|c| $crate::ComponentClone::clone_component(c)) {
+                            prefab.add_with(move |world, entity| {
+                                if let Some(component) = $crate::ComponentClone::clone_component(&component) {
+                                    $crate::Bundle::<World>::insert(component, world, entity);
+                                }
+                            });
+                        }
+                    )*
+                    $(
+                        if self.[<$tag:lower>].contains(entity) {
+                            prefab.add($tag::default());
+                        }
+                    )*
+                }
+                prefab
+            }
+
+            // components passed to `with` on the returned builder override the prefab's
+            pub fn instantiate(&mut self, prefab: &$crate::Prefab<World>) -> EntityBuilder {
+                let entity = self.entities.create();
+                prefab.insert(self, entity);
+                EntityBuilder { world: self, entity }
+            }
+
+            pub fn destroy_entity(&mut self, entity: $crate::Entity) -> bool {
+                if !self.entities.is_alive(entity) {
+                    return false;
+                }
+                $(
+                    self.remove_component::<$struct_ty>(entity);
+                )*
+                $(
+                    self.remove_tag::<$tag>(entity);
+                )*
+                self.dynamic.remove_entity(entity);
+                self.entities.destroy(entity);
+                self.one_frame.retain(|comp| comp.0 != entity);
+                true
+            }
+
+            pub fn is_alive(&self, entity: $crate::Entity) -> bool {
+                self.entities.is_alive(entity)
             }
 
             fn clear_one_frame(&mut self) {
-                for comp in self.one_frame.iter() {
+                for comp in std::mem::take(&mut self.one_frame) {
+                    $(
+                        if <World as $crate::ComponentStorage<$struct_ty>>::ID == comp.1 {
+                            self.remove_component::<$struct_ty>(comp.0);
+                        }
+                    )*
+                }
+            }
+
+            pub fn change_tick(&self) -> u32 {
+                self.change_tick
+            }
+
+            // outside the schedule `Added` and `Changed` see what happened since the last call,
+            // scheduled systems each see what happened since their own previous run
+            pub fn clear_trackers(&mut self) {
+                self.last_change_tick = self.change_tick;
+                self.change_tick += 1;
+            }
+
+            // copies entities, tags and every component marked `rollback`
+            pub fn snapshot(&self) -> Snapshot {
+                paste::paste! {
+                    Snapshot {
+                        $([<$struct_name:lower>]: <$struct_ty as $crate::Rollback>::snapshot(&self.[<$struct_name:lower>]),)*
+                        $([<$tag:lower>]: self.[<$tag:lower>].clone(),)*
+                        entities: self.entities.clone(),
+                        names: self.names.clone(),
+                        one_frame: self.one_frame.clone(),
+                    }
+                }
+            }
+
+            // rollback-safe components are replaced and count as changed, or added where the entity
+            // lacked them. The rest keep their current state, those of entities that did not exist
+            // at the snapshot are removed through `on_remove`. Returns the entities that are alive
+            // again, they only carry rollback-safe components and tags
+            pub fn restore(&mut self, snapshot: &Snapshot) -> Vec<$crate::Entity> {
+                let removed: Vec<_> = self.entities.alive().iter().copied()
+                    .filter(|entity| !snapshot.entities.is_alive(*entity))
+                    .collect();
+                let revived: Vec<_> = snapshot.entities.alive().iter().copied()
+                    .filter(|entity| !self.entities.is_alive(*entity))
+                    .collect();
+
                 paste::paste! {
                     $(
-                        if $struct_name::get_id() == comp.1 {
-                            self.[<$struct_name:lower>].retain(|c| c.entity() != comp.0);
-                            if let Some(comp_count) = self.entities.get(&comp.0) {
-                                self.entities.insert(comp.0, comp_count - 1);
+                        if snapshot.[<$struct_name:lower>].is_none() {
+                            for entity in removed.iter() {
+                                self.remove_component::<$struct_ty>(*entity);
                             }
                         }
                     )*
                 }
+
+                self.entities.restore(&snapshot.entities);
+                self.names = snapshot.names.clone();
+                self.one_frame = snapshot.one_frame.clone();
+                let tick = self.change_tick;
+                paste::paste! {
+                    $(
+                        if let Some(mut storage) = snapshot.[<$struct_name:lower>].as_ref().and_then(<$struct_ty as $crate::Rollback>::snapshot) {
+                            for entity in storage.entities().to_vec() {
+                                if self.[<$struct_name:lower>].contains(entity) {
+                                    storage.set_changed(entity, tick);
+                                } else {
+                                    storage.set_added(entity, tick);
+                                }
+                            }
+                            self.[<$struct_name:lower>] = storage;
+                        }
+                    )*
+                    $(self.[<$tag:lower>] = snapshot.[<$tag:lower>].clone();)*
+                }
+                let entities = &self.entities;
+                self.dynamic.retain(|entity| entities.is_alive(entity));
+                revived
             }
-                self.one_frame.clear();
+
+            pub fn ticks<T>(&self, entity: $crate::Entity) -> Option<$crate::ComponentTicks> where Self: $crate::ComponentStorage<T> {
+                $crate::ComponentStorage::<T>::storage(self).ticks(entity)
+            }
+
+            pub fn insert_resource<T: 'static>(&mut self, value: T) -> Option<T> {
+                self.resources.insert(value)
+            }
+
+            pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+                self.resources.remove::<T>()
+            }
+
+            pub fn resource<T: 'static>(&self) -> Option<&T> {
+                self.resources.get::<T>()
+            }
+
+            pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+                self.resources.get_mut::<T>()
+            }
+
+            pub fn send_event<T: 'static>(&mut self, event: T) {
+                self.events.get_or_insert::<T>().send(event);
+            }
+
+            pub fn event_writer<T: 'static>(&mut self) -> $crate::EventWriter<T> {
+                $crate::EventWriter::new(self.events.get_or_insert::<T>())
+            }
+
+            pub fn event_reader<T: 'static>(&self) -> $crate::EventReader<T> {
+                self.events.get::<T>().map(|events| events.reader()).unwrap_or_default()
+            }
+
+            pub fn events<T: 'static>(&self) -> Option<&$crate::Events<T>> {
+                self.events.get::<T>()
+            }
+
+            pub fn read_events<'a, T: 'static>(&'a self, reader: &mut $crate::EventReader<T>) -> impl Iterator<Item = &'a T> {
+                self.events.get::<T>().map(|events| events.read(reader)).into_iter().flatten()
+            }
+
+            // swaps the event buffers, call once per frame
+            pub fn update_events(&mut self) {
+                self.events.update();
+            }
+
+            // queued on the world and applied at the next sync point, the handle doesn't borrow the world
+            pub fn commands(&self) -> Commands {
+                Commands {
+                    queue: std::rc::Rc::clone(&self.commands),
+                    reserver: self.entities.reserver(),
+                }
+            }
+
+            // a separate queue that only runs when passed to `apply`
+            pub fn command_queue(&self) -> Commands {
+                Commands {
+                    queue: std::rc::Rc::new($crate::CommandQueue::new()),
+                    reserver: self.entities.reserver(),
+                }
+            }
+
+            pub fn apply(&mut self, commands: Commands) {
+                self.entities.flush();
+                loop {
+                    let queued = commands.queue.take();
+                    if queued.is_empty() {
+                        break;
+                    }
+                    for command in queued {
+                        command(self);
+                    }
+                    self.entities.flush();
+                }
+                self.apply_commands();
+            }
+
+            // sync point: reserved entities come alive and queued commands run in order
+            pub fn apply_commands(&mut self) {
+                self.entities.flush();
+                loop {
+                    let commands = self.commands.take();
+                    if commands.is_empty() {
+                        break;
+                    }
+                    for command in commands {
+                        command(self);
+                    }
+                    self.entities.flush();
+                }
+            }
+
+            // entities without any serializable component are left out
+            pub fn save(&self) -> SavedWorld {
+                let mut entities = Vec::new();
+                for entity in self.entities.alive() {
+                    paste::paste! {
+                        let saved = SavedEntity {
+                            entity: *entity,
+                            $(
+                                [<$struct_name:lower>]: self.[<$struct_name:lower>].get(*entity).and_then($crate::SaveComponent::save),
+                            )*
+                            $(
+                                [<$tag:lower>]: self.[<$tag:lower>].contains(*entity),
+                            )*
+                        };
+                    }
+                    if !saved.is_empty() {
+                        entities.push(saved);
+                    }
+                }
+                SavedWorld { entities }
+            }
+
+            // loaded entities are created fresh, the returned map tells where each saved one went
+            pub fn load(&mut self, data: SavedWorld) -> $crate::EntityMap {
+                let mut map = $crate::EntityMap::new();
+                for saved in data.entities.iter() {
+                    map.insert(saved.entity, self.entities.create());
+                }
+                for saved in data.entities {
+                    let entity = map.map(saved.entity);
+                    paste::paste! {
+                        $(
+                            if let Some(mut component) = saved.[<$struct_name:lower>].and_then(<$struct_ty as $crate::SaveComponent>::load) {
+                                $crate::SaveComponent::map_entities(&mut component, &map);
+                                $crate::Bundle::<World>::insert(component, self, entity);
+                            }
+                        )*
+                        $(
+                            if saved.[<$tag:lower>] {
+                                self.add_tag(entity, $tag::default());
+                            }
+                        )*
+                    }
+                }
+                map
+            }
+
+            pub fn save_ron(&self) -> Result<String, $crate::ron::Error> {
+                $crate::ron::ser::to_string_pretty(&self.save(), $crate::ron::ser::PrettyConfig::default())
+            }
+
+            pub fn load_ron(&mut self, data: &str) -> Result<$crate::EntityMap, $crate::ron::error::SpannedError> {
+                Ok(self.load($crate::ron::from_str(data)?))
+            }
+
+            pub fn save_json(&self) -> Result<String, $crate::serde_json::Error> {
+                $crate::serde_json::to_string_pretty(&self.save())
+            }
+
+            pub fn load_json(&mut self, data: &str) -> Result<$crate::EntityMap, $crate::serde_json::Error> {
+                Ok(self.load($crate::serde_json::from_str(data)?))
+            }
+
+            // the first entity given the name if several share it
+            pub fn find_by_name(&self, name: &str) -> Option<$crate::Entity> {
+                self.find_all_by_name(name).first().copied()
+            }
+
+            // checked against the current `Name`, a rename through `query_mut` is not indexed
+            pub fn find_all_by_name(&self, name: &str) -> Vec<$crate::Entity> {
+                self.names
+                    .get_all(name)
+                    .iter()
+                    .copied()
+                    .filter(|entity| self.name.get(*entity).is_some_and(|current| current.as_str() == name))
+                    .collect()
+            }
+
+            pub fn add_tag<T>(&mut self, entity: $crate::Entity, tag: T) where Self: $crate::ComponentStorage<T> {
+                if self.entities.is_alive(entity) {
+                    let tick = self.change_tick;
+                    $crate::ComponentStorage::<T>::storage_mut(self).insert_at(entity, tag, tick);
+                }
+            }
+
+            pub fn remove_tag<T>(&mut self, entity: $crate::Entity) -> bool where Self: $crate::ComponentStorage<T> {
+                $crate::ComponentStorage::<T>::storage_mut(self).remove(entity).is_some()
+            }
+
+            pub fn has_tag<T>(&self, entity: $crate::Entity) -> bool where Self: $crate::ComponentStorage<T> {
+                $crate::ComponentStorage::<T>::storage(self).contains(entity)
             }
 
             pub fn query(&self) -> Query {
                 Query {
                     world: self,
-                    query: Vec::new(),
+                    groups: vec![$crate::QueryGroup::default()],
                 }
             }
 
@@ -64,67 +457,407 @@ macro_rules! init_ecs {
                     $(
                         comp_count += self.[<$struct_name:lower>].len();
                     )*
+                    $(
+                        comp_count += self.[<$tag:lower>].len();
+                    )*
                 }
+                comp_count += self.dynamic.len();
                 (self.entities.len(), comp_count)
             }
 
+            fn entities_of(&self, id: usize) -> &[$crate::Entity] {
+                paste::paste! {
+                    $(
+                        if <World as $crate::ComponentStorage<$struct_ty>>::ID == id {
+                            return self.[<$struct_name:lower>].entities();
+                        }
+                    )*
+                    $(
+                        if <World as $crate::ComponentStorage<$tag>>::ID == id {
+                            return self.[<$tag:lower>].entities();
+                        }
+                    )*
+                }
+                match self.registry.info(id) {
+                    Some(info) => self.dynamic.entities(info.type_id),
+                    None => &[],
+                }
+            }
+
+            pub fn components(&self) -> &$crate::ComponentRegistry {
+                &self.registry
+            }
+
+            pub fn component_id<T: 'static>(&self) -> Option<usize> {
+                self.registry.id::<T>()
+            }
+
+            // components from plugin crates, stored next to the ones listed in `init_ecs!`
+            pub fn register_component<T: 'static>(&mut self) -> usize {
+                if self.registry.id::<T>().is_none() {
+                    self.dynamic.register::<T>();
+                }
+                self.registry.register::<T>()
+            }
+
+            // registers the component on first use, not saved, cloned or snapshotted. The value is
+            // handed back for dead entities and for components listed in `init_ecs!`, those use `add`
+            pub fn insert_dynamic<T: 'static>(&mut self, entity: $crate::Entity, value: T) -> Result<Option<T>, T> {
+                if !self.entities.is_alive(entity) {
+                    return Err(value);
+                }
+                self.register_component::<T>();
+                let tick = self.change_tick;
+                match self.dynamic.storage_mut::<T>() {
+                    Some(storage) => Ok(storage.insert_at(entity, value, tick)),
+                    None => Err(value),
+                }
+            }
+
+            pub fn remove_dynamic<T: 'static>(&mut self, entity: $crate::Entity) -> Option<T> {
+                self.dynamic.storage_mut::<T>()?.remove(entity)
+            }
+
+            pub fn get_dynamic<T: 'static>(&self, entity: $crate::Entity) -> Option<&T> {
+                self.dynamic.storage::<T>()?.get(entity)
+            }
+
+            pub fn get_dynamic_mut<T: 'static>(&mut self, entity: $crate::Entity) -> Option<&mut T> {
+                let tick = self.change_tick;
+                let storage = self.dynamic.storage_mut::<T>()?;
+                storage.set_changed(entity, tick);
+                storage.get_mut(entity)
+            }
+
+            pub fn dynamic_storage<T: 'static>(&self) -> Option<&$crate::SparseSet<T>> {
+                self.dynamic.storage::<T>()
+            }
+
+            pub fn has_component(&self, id: usize, entity: $crate::Entity) -> bool {
+                paste::paste! {
+                    $(
+                        if <World as $crate::ComponentStorage<$struct_ty>>::ID == id {
+                            return self.[<$struct_name:lower>].contains(entity);
+                        }
+                    )*
+                    $(
+                        if <World as $crate::ComponentStorage<$tag>>::ID == id {
+                            return self.[<$tag:lower>].contains(entity);
+                        }
+                    )*
+                }
+                self.registry.info(id).is_some_and(|info| self.dynamic.contains(info.type_id, entity))
+            }
+
+            // every component of the entity with its component id, for inspectors and tools
+            pub fn reflect(&self, entity: $crate::Entity) -> Vec<(usize, &dyn $crate::Reflect)> {
+                let mut components: Vec<(usize, &dyn $crate::Reflect)> = Vec::new();
+                paste::paste! {
+                    $(
+                        if let Some(component) = self.[<$struct_name:lower>].get(entity) {
+                            components.push((<World as $crate::ComponentStorage<$struct_ty>>::ID, component));
+                        }
+                    )*
+                }
+                components
+            }
+
+            pub fn reflect_component(&self, id: usize, entity: $crate::Entity) -> Option<&dyn $crate::Reflect> {
+                paste::paste! {
+                    $(
+                        if <World as $crate::ComponentStorage<$struct_ty>>::ID == id {
+                            return self.[<$struct_name:lower>].get(entity).map(|c| c as &dyn $crate::Reflect);
+                        }
+                    )*
+                }
+                None
+            }
+
+            // counts as a change like any other mutable borrow
+            pub fn reflect_component_mut(&mut self, id: usize, entity: $crate::Entity) -> Option<&mut dyn $crate::Reflect> {
+                $(
+                    if <World as $crate::ComponentStorage<$struct_ty>>::ID == id {
+                        return self.get_mut::<$struct_ty>(entity).map(|c| c as &mut dyn $crate::Reflect);
+                    }
+                )*
+                None
+            }
+
+            pub fn get<T>(&self, entity: $crate::Entity) -> Option<&T> where Self: $crate::ComponentStorage<T> {
+                $crate::ComponentStorage::<T>::storage(self).get(entity)
+            }
+
+            pub fn get_mut<T>(&mut self, entity: $crate::Entity) -> Option<&mut T> where Self: $crate::ComponentStorage<T> {
+                let tick = self.change_tick;
+                let storage = $crate::ComponentStorage::<T>::storage_mut(self);
+                storage.set_changed(entity, tick);
+                storage.get_mut(entity)
+            }
+
+            pub fn remove_component<T: $crate::ComponentHooks<World>>(&mut self, entity: $crate::Entity) -> Option<T> where Self: $crate::ComponentStorage<T> {
+                if !$crate::ComponentStorage::<T>::storage(self).contains(entity) {
+                    return None;
+                }
+                T::on_remove(self, entity);
+                $crate::ComponentStorage::<T>::storage_mut(self).remove(entity)
+            }
+
+            pub fn query_mut<'w, Q: $crate::Fetch<'w, World>>(&'w mut self) -> $crate::QueryIter<'w, World, Q> {
+                $crate::QueryIter::new(self)
+            }
+
+            pub fn query_ref<'w, Q: $crate::Fetch<'w, World> + $crate::ReadOnlyFetch>(&'w self) -> $crate::QueryIter<'w, World, Q> {
+                $crate::QueryIter::new_read_only(self)
+            }
+
             // create methods
 
             $(
                 paste::paste! {
-                        pub fn [<get_$struct_name:lower>](&self, entity: usize) -> Option<&mut $struct_name> {
-
-                                for item in &self.[<$struct_name:lower>] {
-                                    let item = unsafe { &mut *(item as *const _ as *mut $struct_name) };
-                                    if item.entity() == entity {
-                                        return Some(item)
-                                    }
+                        pub fn [<get_$struct_name:lower>](&self, entity: $crate::Entity) -> Option<&$struct_ty> {
+                            self.[<$struct_name:lower>].get(entity)
                         }
-                            None
+                }
+            )*
+
+            $(
+                paste::paste! {
+                        pub fn [<get_$mut_name:lower _mut>](&mut self, entity: $crate::Entity) -> Option<&mut $mut_name> {
+                            self.get_mut::<$mut_name>(entity)
                         }
                 }
             )*
         }
 
+        impl $crate::DynamicStorage for World {
+            unsafe fn dynamic_ptr(world: *mut Self) -> *const $crate::DynamicComponents {
+                std::ptr::addr_of!((*world).dynamic)
+            }
+        }
+
+        impl $crate::EntityStorage for World {
+            unsafe fn entities_ptr(world: *mut Self) -> *const $crate::Entities {
+                std::ptr::addr_of!((*world).entities)
+            }
+
+            unsafe fn change_tick(world: *mut Self) -> u32 {
+                std::ptr::addr_of!((*world).change_tick).read()
+            }
+
+            unsafe fn last_change_tick(world: *mut Self) -> u32 {
+                std::ptr::addr_of!((*world).last_change_tick).read()
+            }
+        }
+
+        impl $crate::SystemTicks for World {
+            fn begin_system(&mut self, last_run: u32) {
+                self.last_change_tick = last_run;
+            }
+
+            fn end_system(&mut self) -> u32 {
+                let tick = self.change_tick;
+                self.change_tick += 1;
+                tick
+            }
+        }
+
+        $(
+            paste::paste! {
+                impl $crate::ComponentStorage<$struct_ty> for World {
+                    const ID: usize = ComponentIndex::$struct_name as usize;
+
+                    fn storage(&self) -> &$crate::SparseSet<$struct_ty> {
+                        &self.[<$struct_name:lower>]
+                    }
+
+                    fn storage_mut(&mut self) -> &mut $crate::SparseSet<$struct_ty> {
+                        &mut self.[<$struct_name:lower>]
+                    }
+
+                    unsafe fn storage_ptr(world: *mut Self) -> *mut $crate::SparseSet<$struct_ty> {
+                        std::ptr::addr_of_mut!((*world).[<$struct_name:lower>])
+                    }
+                }
+            }
+        )*
+
+        $(
+            paste::paste! {
+                impl $crate::ComponentStorage<$tag> for World {
+                    const ID: usize = ComponentIndex::$tag as usize;
+
+                    fn storage(&self) -> &$crate::SparseSet<$tag> {
+                        &self.[<$tag:lower>]
+                    }
+
+                    fn storage_mut(&mut self) -> &mut $crate::SparseSet<$tag> {
+                        &mut self.[<$tag:lower>]
+                    }
+
+                    unsafe fn storage_ptr(world: *mut Self) -> *mut $crate::SparseSet<$tag> {
+                        std::ptr::addr_of_mut!((*world).[<$tag:lower>])
+                    }
+                }
+            }
+        )*
+
+        // init spawn builder
+
+        pub struct EntityBuilder<'a> {
+            world: &'a mut World,
+            entity: $crate::Entity,
+        }
+
+        impl<'a> EntityBuilder<'a> {
+            pub fn with<B: $crate::Bundle<World>>(self, bundle: B) -> Self {
+                bundle.insert(self.world, self.entity);
+                self
+            }
+
+            pub fn id(&self) -> $crate::Entity {
+                self.entity
+            }
+        }
+
+        // init commands
+
+        pub struct Commands {
+            queue: std::rc::Rc<$crate::CommandQueue<World>>,
+            reserver: $crate::EntityReserver,
+        }
+
+        impl Commands {
+            // the entity can be used right away but only comes alive when commands are applied
+            pub fn spawn(&self) -> $crate::Entity {
+                self.reserver.reserve()
+            }
+
+            pub fn insert<C: AddComponent + 'static>(&self, component: C) {
+                self.add(move |world| component.add(world));
+            }
+
+            pub fn remove<T: $crate::ComponentHooks<World> + 'static>(&self, entity: $crate::Entity) where World: $crate::ComponentStorage<T> {
+                self.add(move |world| {
+                    world.remove_component::<T>(entity);
+                });
+            }
+
+            pub fn despawn(&self, entity: $crate::Entity) {
+                self.add(move |world| {
+                    world.destroy_entity(entity);
+                });
+            }
+
+            pub fn add(&self, command: impl FnOnce(&mut World) + 'static) {
+                self.queue.push(command);
+            }
+
+            pub fn len(&self) -> usize {
+                self.queue.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.queue.is_empty()
+            }
+        }
+
         // init queries
 
         pub struct Query<'a> {
             world: &'a World,
-            query: Vec<usize>,
+            groups: Vec<$crate::QueryGroup>,
         }
 
         impl<'a> Query<'a> {
             $(
                 paste::paste! {
                     pub fn [<$struct_name:lower>](mut self) -> Query<'a> {
-                        self.query.push($struct_name::get_id());
+                        self.group().with.push(<World as $crate::ComponentStorage<$struct_ty>>::ID);
+                        self
+                    }
+
+                    pub fn [<without_$struct_name:lower>](mut self) -> Query<'a> {
+                        self.group().without.push(<World as $crate::ComponentStorage<$struct_ty>>::ID);
                         self
                     }
             }
             )*
 
-            paste::paste! {
-                pub fn fetch(&self) -> Vec<usize> {
-                    let mut entities: Option<Vec<usize>> = None;
-                    for e in self.query.iter() {
-                        let mut new_entities = Vec::<usize>::new();
-                        match e {
-                        $(
-                            [<$struct_name:lower>] if *[<$struct_name:lower>] == $struct_name::get_id() => {
-                                for comp in &self.world.[<$struct_name:lower>] {
-                                    new_entities.push(comp.entity);
-                                }
-                            },
-                        )*
-                        _ => (),
-                        }
-                        if let Some(entities) = &mut entities {
-                            entities.retain(|e| new_entities.contains(e));
-                        } else {
-                            entities = Some(new_entities);
-                        }
+            $(
+                paste::paste! {
+                    pub fn [<$tag:lower>](mut self) -> Query<'a> {
+                        self.group().with.push(<World as $crate::ComponentStorage<$tag>>::ID);
+                        self
+                    }
+
+                    pub fn [<without_$tag:lower>](mut self) -> Query<'a> {
+                        self.group().without.push(<World as $crate::ComponentStorage<$tag>>::ID);
+                        self
                     }
-                    entities.unwrap()
+            }
+            )*
+
+            // works for any registered component, including runtime ones
+            pub fn with<T: 'static>(mut self) -> Query<'a> {
+                let id = self.world.component_id::<T>().unwrap_or(usize::MAX);
+                self.group().with.push(id);
+                self
+            }
+
+            pub fn without<T: 'static>(mut self) -> Query<'a> {
+                if let Some(id) = self.world.component_id::<T>() {
+                    self.group().without.push(id);
+                }
+                self
+            }
+
+            // everything after `or` is matched as an alternative to what came before
+            pub fn or(mut self) -> Query<'a> {
+                self.groups.push($crate::QueryGroup::default());
+                self
+            }
+
+            fn group(&mut self) -> &mut $crate::QueryGroup {
+                self.groups.last_mut().unwrap()
+            }
+
+            fn matches(&self, group: &$crate::QueryGroup, entity: $crate::Entity) -> bool {
+                group.matches(|id| self.world.has_component(id, entity))
+            }
+
+            fn iter(&self) -> impl Iterator<Item = $crate::Entity> + '_ {
+                self.groups.iter().enumerate().flat_map(move |(i, group)| {
+                    // iterate the smallest set and probe the others
+                    let candidates = group.with
+                        .iter()
+                        .map(|id| self.world.entities_of(*id))
+                        .min_by_key(|entities| entities.len())
+                        .unwrap_or(self.world.entities.alive());
+
+                    candidates.iter().copied().filter(move |e| {
+                        self.matches(group, *e) && !self.groups[..i].iter().any(|g| self.matches(g, *e))
+                    })
+                })
+            }
+
+            pub fn fetch(&self) -> Vec<$crate::Entity> {
+                self.iter().collect()
+            }
+
+            pub fn any(&self) -> bool {
+                self.iter().next().is_some()
+            }
+
+            pub fn count(&self) -> usize {
+                self.iter().count()
+            }
+
+            // the only matching entity, `None` if there are zero or several
+            pub fn single(&self) -> Option<$crate::Entity> {
+                let mut iter = self.iter();
+                match (iter.next(), iter.next()) {
+                    (Some(entity), None) => Some(entity),
+                    _ => None,
                 }
             }
         }
@@ -138,25 +871,59 @@ macro_rules! init_ecs {
 
         $(
             paste::paste! {
-            impl AddComponent for $struct_name {
+            impl AddComponent for $struct_ty {
 
                     fn add(self, world: &mut World) {
+                        if !world.entities.is_alive(self.entity()) {
+                            return;
+                        }
+                        let entity = self.entity();
+                        // a replaced component is removed first so its hooks stay balanced
+                        if world.[<$struct_name:lower>].contains(entity) {
+                            <$struct_ty as $crate::ComponentHooks<World>>::on_remove(world, entity);
+                        }
                         if self.one_frame() {
-                            world.one_frame.push((self.entity(), $struct_name::get_id()))
+                            world.one_frame.push((entity, <World as $crate::ComponentStorage<$struct_ty>>::ID))
                         }
-                        world.entities.entry(self.entity()).and_modify(|e| *e += 1);
-                        world.[<$struct_name:lower>].push(self);
+                        let tick = world.change_tick;
+                        world.[<$struct_name:lower>].insert_at(entity, self, tick);
+                        <$struct_ty as $crate::ComponentHooks<World>>::on_add(world, entity);
                     }
 
                     fn remove(&self, world: &mut World) {
-                        let cnt = world.[<$struct_name:lower>].len();
-                        world.[<$struct_name:lower>].retain(|c| c.entity() != self.entity());
-                        if world.[<$struct_name:lower>].len() != cnt {
-                            world.entities.entry(self.entity()).and_modify(|e| *e -= 1);
-                        }
+                        world.remove_component::<$struct_ty>(self.entity());
                     }
             }
+
+            impl $crate::Bundle<World> for $struct_ty {
+                fn insert(mut self, world: &mut World, entity: $crate::Entity) {
+                    self.entity = entity;
+                    self.add(world);
+                }
+            }
         }
         )*
+
+        $(
+            impl $crate::Bundle<World> for $tag {
+                fn insert(self, world: &mut World, entity: $crate::Entity) {
+                    world.add_tag(entity, self);
+                }
+            }
+        )*
+
+        // keeps the name index in sync
+
+        impl $crate::ComponentHooks<World> for $crate::Name {
+            fn on_add(world: &mut World, entity: $crate::Entity) {
+                if let Some(name) = world.name.get(entity) {
+                    world.names.insert(name.as_str(), entity);
+                }
+            }
+
+            fn on_remove(world: &mut World, entity: $crate::Entity) {
+                world.names.remove(entity);
+            }
+        }
     };
 }
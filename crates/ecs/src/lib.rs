@@ -1,5 +1,33 @@
+mod commands;
+mod dynamic;
+mod entity;
+mod events;
+mod name;
+mod prefab;
+mod query;
+mod reflect;
+mod registry;
+mod resources;
+mod save;
+mod storage;
 mod types;
 
 pub mod init;
 
-pub use types::Component;
+pub use commands::CommandQueue;
+pub use dynamic::{Dyn, DynMut, DynamicComponents, DynamicStorage};
+pub use entity::{Entities, Entity, EntityReserver};
+pub use events::{EventChannels, EventReader, EventWriter, Events};
+pub use name::{Name, NameIndex};
+pub use prefab::Prefab;
+pub use query::{Access, Added, Changed, ComponentStorage, EntityStorage, Fetch, QueryGroup, QueryIter, ReadOnlyFetch, SystemTicks};
+pub use reflect::{FieldInfo, Reflect, ReflectValue, Value};
+pub use registry::{ComponentInfo, ComponentRegistry};
+pub use resources::Resources;
+pub use save::{EntityMap, SaveComponent};
+pub use storage::{ComponentTicks, SparseSet};
+pub use types::{Bundle, Component, ComponentClone, ComponentHooks, Rollback};
+
+pub use ron;
+pub use serde;
+pub use serde_json;
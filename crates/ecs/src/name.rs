@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Component, ComponentClone, Entity, FieldInfo, Reflect, Rollback, SaveComponent, SparseSet, Value};
+
+// built into every world, `find_by_name` looks entities up through it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Name {
+    pub entity: Entity,
+    pub one_frame: bool,
+    name: String,
+}
+
+impl Name {
+    pub fn new(name: &str) -> Self {
+        Self {
+            entity: Entity::default(),
+            one_frame: false,
+            name: name.to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Component for Name {
+    fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    fn one_frame(&self) -> bool {
+        self.one_frame
+    }
+}
+
+impl ComponentClone for Name {
+    fn clone_component(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+}
+
+impl SaveComponent for Name {
+    type Saved = Self;
+
+    fn save(&self) -> Option<Self> {
+        Some(self.clone())
+    }
+
+    fn load(saved: Self) -> Option<Self> {
+        Some(saved)
+    }
+}
+
+impl Rollback for Name {
+    fn snapshot(storage: &SparseSet<Self>) -> Option<SparseSet<Self>> {
+        Some(storage.clone())
+    }
+}
+
+// renaming goes through a new `Name` so the index sees it
+impl Reflect for Name {
+    fn type_name(&self) -> &'static str {
+        "Name"
+    }
+
+    fn fields(&self) -> &'static [FieldInfo] {
+        const FIELDS: &[FieldInfo] = &[FieldInfo {
+            name: "name",
+            type_name: "String",
+            editable: false,
+        }];
+        FIELDS
+    }
+
+    fn field(&self, name: &str) -> Option<Value> {
+        match name {
+            "name" => Some(Value::String(self.name.clone())),
+            _ => None,
+        }
+    }
+
+    fn set_field(&mut self, _name: &str, _value: Value) -> bool {
+        false
+    }
+}
+
+// entities by name, kept in sync by the `Name` hooks of each world. Entries are removed by
+// entity, so a `Name` changed in place can't leave a stale one behind
+#[derive(Clone, Default)]
+pub struct NameIndex {
+    entities: HashMap<String, Vec<Entity>>,
+    names: HashMap<Entity, String>,
+}
+
+impl NameIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // replaces the entity's previous entry
+    pub fn insert(&mut self, name: &str, entity: Entity) {
+        self.remove(entity);
+        self.entities.entry(name.to_string()).or_default().push(entity);
+        self.names.insert(entity, name.to_string());
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        let name = match self.names.remove(&entity) {
+            Some(name) => name,
+            None => return,
+        };
+        if let Some(entities) = self.entities.get_mut(&name) {
+            entities.retain(|e| *e != entity);
+            if entities.is_empty() {
+                self.entities.remove(&name);
+            }
+        }
+    }
+
+    // the first entity given the name if several share it
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.entities.get(name)?.first().copied()
+    }
+
+    pub fn get_all(&self, name: &str) -> &[Entity] {
+        self.entities.get(name).map_or(&[], |entities| entities.as_slice())
+    }
+}
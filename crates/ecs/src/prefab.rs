@@ -0,0 +1,49 @@
+use crate::{Bundle, Entity};
+
+type Instantiate<W> = Box<dyn Fn(&mut W, Entity)>;
+
+// a template of components, every instance gets its own copy
+pub struct Prefab<W> {
+    components: Vec<Instantiate<W>>,
+}
+
+impl<W> Prefab<W> {
+    pub fn new() -> Self {
+        Self {
+            components: Vec::new(),
+        }
+    }
+
+    pub fn with<B: Bundle<W> + Clone + 'static>(mut self, bundle: B) -> Self {
+        self.add(bundle);
+        self
+    }
+
+    pub fn add<B: Bundle<W> + Clone + 'static>(&mut self, bundle: B) {
+        self.add_with(move |world, entity| bundle.clone().insert(world, entity));
+    }
+
+    pub fn add_with(&mut self, instantiate: impl Fn(&mut W, Entity) + 'static) {
+        self.components.push(Box::new(instantiate));
+    }
+
+    pub fn insert(&self, world: &mut W, entity: Entity) {
+        for component in self.components.iter() {
+            component(world, entity);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+impl<W> Default for Prefab<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
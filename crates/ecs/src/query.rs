@@ -0,0 +1,349 @@
+use std::{any::TypeId, marker::PhantomData};
+
+use crate::{ComponentTicks, Entities, Entity, SparseSet};
+
+pub trait ComponentStorage<T> {
+    const ID: usize;
+
+    fn storage(&self) -> &SparseSet<T>;
+    fn storage_mut(&mut self) -> &mut SparseSet<T>;
+
+    /// # Safety
+    /// `world` must point to a live world. The returned pointer must not be
+    /// turned into a reference that aliases another borrow of the same storage.
+    unsafe fn storage_ptr(world: *mut Self) -> *mut SparseSet<T>;
+}
+
+pub trait EntityStorage {
+    /// # Safety
+    /// `world` must point to a live world.
+    unsafe fn entities_ptr(world: *mut Self) -> *const Entities;
+
+    /// # Safety
+    /// `world` must point to a live world.
+    unsafe fn change_tick(world: *mut Self) -> u32;
+
+    /// # Safety
+    /// `world` must point to a live world.
+    unsafe fn last_change_tick(world: *mut Self) -> u32;
+}
+
+// gives every scheduled system its own `Added`/`Changed` window, from its previous run up to now
+pub trait SystemTicks {
+    fn begin_system(&mut self, last_run: u32);
+    // returns the tick the system ran at, its `last_run` next time
+    fn end_system(&mut self) -> u32;
+}
+
+#[derive(Default)]
+pub struct Access {
+    reads: Vec<(TypeId, &'static str)>,
+    writes: Vec<(TypeId, &'static str)>,
+}
+
+impl Access {
+    pub fn read<T: 'static>(&mut self) {
+        self.reads.push((TypeId::of::<T>(), std::any::type_name::<T>()));
+    }
+
+    pub fn write<T: 'static>(&mut self) {
+        self.writes.push((TypeId::of::<T>(), std::any::type_name::<T>()));
+    }
+
+    // a component written by the query may not be borrowed anywhere else in it
+    pub fn conflict(&self) -> Option<&'static str> {
+        for (i, (id, name)) in self.writes.iter().enumerate() {
+            if self.writes[i + 1..].iter().any(|w| w.0 == *id) || self.reads.iter().any(|r| r.0 == *id) {
+                return Some(name);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct QueryGroup {
+    pub with: Vec<usize>,
+    pub without: Vec<usize>,
+}
+
+impl QueryGroup {
+    pub fn matches(&self, has_component: impl Fn(usize) -> bool) -> bool {
+        self.with.iter().all(|id| has_component(*id)) && !self.without.iter().any(|id| has_component(*id))
+    }
+}
+
+/// # Safety
+/// `access` must report every component the fetch borrows, and mutably
+/// borrowed components must never be handed out twice for the same entity.
+pub unsafe trait Fetch<'w, W> {
+    type Item;
+    type State;
+
+    fn access(access: &mut Access);
+
+    /// # Safety
+    /// `world` must be valid for `'w` and the accesses of the whole query must not conflict.
+    unsafe fn init(world: *mut W) -> Self::State;
+
+    // entities that may match, `None` if the fetch does not restrict the query
+    fn entities(state: &Self::State) -> Option<&'w [Entity]>;
+
+    // checked for the whole query before anything is fetched, so filters see the ticks
+    // from before this iteration borrowed the entity mutably
+    fn matches(_state: &Self::State, _entity: Entity) -> bool {
+        true
+    }
+
+    /// # Safety
+    /// Must be called at most once per entity for each iteration.
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item>;
+}
+
+/// # Safety
+/// Implementors must only hand out shared borrows.
+pub unsafe trait ReadOnlyFetch {}
+
+unsafe impl<'w, W> Fetch<'w, W> for Entity {
+    type Item = Entity;
+    type State = ();
+
+    fn access(_: &mut Access) {}
+
+    unsafe fn init(_: *mut W) -> Self::State {}
+
+    fn entities(_: &Self::State) -> Option<&'w [Entity]> {
+        None
+    }
+
+    unsafe fn fetch(_: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        Some(entity)
+    }
+}
+
+unsafe impl ReadOnlyFetch for Entity {}
+
+unsafe impl<'w, W, F: Fetch<'w, W>> Fetch<'w, W> for Option<F> {
+    type Item = Option<F::Item>;
+    type State = F::State;
+
+    fn access(access: &mut Access) {
+        F::access(access);
+    }
+
+    unsafe fn init(world: *mut W) -> Self::State {
+        F::init(world)
+    }
+
+    fn entities(_: &Self::State) -> Option<&'w [Entity]> {
+        None
+    }
+
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        Some(F::fetch(state, entity))
+    }
+}
+
+unsafe impl<F: ReadOnlyFetch> ReadOnlyFetch for Option<F> {}
+
+unsafe impl<'w, W: ComponentStorage<T> + 'w, T: 'static> Fetch<'w, W> for &'w T {
+    type Item = &'w T;
+    type State = &'w SparseSet<T>;
+
+    fn access(access: &mut Access) {
+        access.read::<T>();
+    }
+
+    unsafe fn init(world: *mut W) -> Self::State {
+        &*W::storage_ptr(world)
+    }
+
+    fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+        Some(state.entities())
+    }
+
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        state.get(entity)
+    }
+}
+
+unsafe impl<T> ReadOnlyFetch for &T {}
+
+// a mutable borrow counts as a change
+unsafe impl<'w, W: ComponentStorage<T> + EntityStorage + 'w, T: 'static> Fetch<'w, W> for &'w mut T {
+    type Item = &'w mut T;
+    type State = (&'w SparseSet<T>, *mut T, *mut ComponentTicks, u32);
+
+    fn access(access: &mut Access) {
+        access.write::<T>();
+    }
+
+    unsafe fn init(world: *mut W) -> Self::State {
+        let storage = W::storage_ptr(world);
+        let data = (*storage).data_ptr();
+        let ticks = (*storage).ticks_ptr();
+        (&*storage, data, ticks, W::change_tick(world))
+    }
+
+    fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+        Some(state.0.entities())
+    }
+
+    unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+        let dense = state.0.dense_index(entity)?;
+        (*state.2.add(dense)).changed = state.3;
+        Some(&mut *state.1.add(dense))
+    }
+}
+
+// matches components inserted since the world last cleared its trackers
+pub struct Added<T>(PhantomData<fn() -> T>);
+
+// matches components inserted or borrowed mutably since the world last cleared its trackers
+pub struct Changed<T>(PhantomData<fn() -> T>);
+
+macro_rules! impl_fetch_tick_filter {
+    ($name:ident, $check:ident) => {
+        unsafe impl<'w, W: ComponentStorage<T> + EntityStorage + 'w, T: 'static> Fetch<'w, W> for $name<T> {
+            type Item = ();
+            type State = (&'w SparseSet<T>, u32);
+
+            // only ticks are read, which never alias a borrowed component
+            fn access(_: &mut Access) {}
+
+            unsafe fn init(world: *mut W) -> Self::State {
+                (&*W::storage_ptr(world), W::last_change_tick(world))
+            }
+
+            fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+                Some(state.0.entities())
+            }
+
+            fn matches(state: &Self::State, entity: Entity) -> bool {
+                state.0.ticks(entity).is_some_and(|ticks| ticks.$check(state.1))
+            }
+
+            unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+                state.0.contains(entity).then_some(())
+            }
+        }
+
+        unsafe impl<T> ReadOnlyFetch for $name<T> {}
+    };
+}
+
+impl_fetch_tick_filter!(Added, is_added);
+impl_fetch_tick_filter!(Changed, is_changed);
+
+macro_rules! impl_fetch_tuple {
+    ($($name:ident),*) => {
+        #[allow(non_snake_case)]
+        unsafe impl<'w, W, $($name: Fetch<'w, W>),*> Fetch<'w, W> for ($($name,)*) {
+            type Item = ($($name::Item,)*);
+            type State = ($($name::State,)*);
+
+            fn access(access: &mut Access) {
+                $($name::access(access);)*
+            }
+
+            unsafe fn init(world: *mut W) -> Self::State {
+                ($($name::init(world),)*)
+            }
+
+            fn entities(state: &Self::State) -> Option<&'w [Entity]> {
+                let ($($name,)*) = state;
+                let mut smallest: Option<&'w [Entity]> = None;
+                $(
+                    if let Some(entities) = $name::entities($name) {
+                        if smallest.map_or(true, |s| entities.len() < s.len()) {
+                            smallest = Some(entities);
+                        }
+                    }
+                )*
+                smallest
+            }
+
+            fn matches(state: &Self::State, entity: Entity) -> bool {
+                let ($($name,)*) = state;
+                $($name::matches($name, entity))&&*
+            }
+
+            unsafe fn fetch(state: &mut Self::State, entity: Entity) -> Option<Self::Item> {
+                let ($($name,)*) = state;
+                Some(($($name::fetch($name, entity)?,)*))
+            }
+        }
+
+        unsafe impl<$($name: ReadOnlyFetch),*> ReadOnlyFetch for ($($name,)*) {}
+    };
+}
+
+impl_fetch_tuple!(A);
+impl_fetch_tuple!(A, B);
+impl_fetch_tuple!(A, B, C);
+impl_fetch_tuple!(A, B, C, D);
+impl_fetch_tuple!(A, B, C, D, E);
+impl_fetch_tuple!(A, B, C, D, E, F);
+impl_fetch_tuple!(A, B, C, D, E, F, G);
+impl_fetch_tuple!(A, B, C, D, E, F, G, H);
+
+pub struct QueryIter<'w, W, Q: Fetch<'w, W>> {
+    state: Q::State,
+    entities: &'w [Entity],
+    index: usize,
+    marker: PhantomData<&'w mut W>,
+}
+
+impl<'w, W: EntityStorage, Q: Fetch<'w, W>> QueryIter<'w, W, Q> {
+    pub fn new(world: &'w mut W) -> Self {
+        let mut access = Access::default();
+        Q::access(&mut access);
+        if let Some(name) = access.conflict() {
+            panic!("query borrows {} mutably more than once", name);
+        }
+
+        // SAFETY: the world is exclusively borrowed for 'w and the accesses are disjoint
+        unsafe { Self::from_ptr(world as *mut W) }
+    }
+
+    pub fn new_read_only(world: &'w W) -> Self
+    where
+        Q: ReadOnlyFetch,
+    {
+        // SAFETY: read-only fetches never write through the pointer
+        unsafe { Self::from_ptr(world as *const W as *mut W) }
+    }
+
+    unsafe fn from_ptr(world: *mut W) -> Self {
+        let state = Q::init(world);
+        // queries made only of optional fetches visit every alive entity
+        let entities = match Q::entities(&state) {
+            Some(entities) => entities,
+            None => (*W::entities_ptr(world)).alive(),
+        };
+        Self {
+            state,
+            entities,
+            index: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'w, W, Q: Fetch<'w, W>> Iterator for QueryIter<'w, W, Q> {
+    type Item = Q::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(entity) = self.entities.get(self.index) {
+            self.index += 1;
+            if !Q::matches(&self.state, *entity) {
+                continue;
+            }
+            // SAFETY: every entity appears once in a storage, so items never alias
+            if let Some(item) = unsafe { Q::fetch(&mut self.state, *entity) } {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,75 @@
+use math::{UnitQuaternion, Vector2f, Vector3d, Vector3f};
+
+use crate::Entity;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    Usize(usize),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Entity(Entity),
+    Vector2f(Vector2f),
+    Vector3f(Vector3f),
+    Vector3d(Vector3d),
+    UnitQuaternion(UnitQuaternion),
+}
+
+#[derive(Clone, Debug)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub type_name: &'static str,
+    // false for fields without a `Value` representation or marked `#[reflect(read_only)]`,
+    // fields marked `#[reflect(skip)]` are not listed at all
+    pub editable: bool,
+}
+
+pub trait Reflect {
+    fn type_name(&self) -> &'static str;
+    fn fields(&self) -> &'static [FieldInfo];
+    fn field(&self, name: &str) -> Option<Value>;
+    // false if the field is unknown, not editable or the value has the wrong type
+    fn set_field(&mut self, name: &str, value: Value) -> bool;
+}
+
+pub trait ReflectValue: Sized {
+    fn to_value(&self) -> Value;
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+macro_rules! impl_reflect_value {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl ReflectValue for $ty {
+                fn to_value(&self) -> Value {
+                    Value::$variant(self.clone())
+                }
+
+                fn from_value(value: Value) -> Option<Self> {
+                    match value {
+                        Value::$variant(value) => Some(value),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_reflect_value!(
+    bool => Bool,
+    i32 => I32,
+    u32 => U32,
+    usize => Usize,
+    f32 => F32,
+    f64 => F64,
+    String => String,
+    Entity => Entity,
+    Vector2f => Vector2f,
+    Vector3f => Vector3f,
+    Vector3d => Vector3d,
+    UnitQuaternion => UnitQuaternion
+);
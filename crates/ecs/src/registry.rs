@@ -0,0 +1,67 @@
+use std::{any::TypeId, collections::HashMap};
+
+#[derive(Clone, Debug)]
+pub struct ComponentInfo {
+    pub id: usize,
+    pub type_id: TypeId,
+    pub name: &'static str,
+    pub size: usize,
+}
+
+#[derive(Default)]
+pub struct ComponentRegistry {
+    components: Vec<ComponentInfo>,
+    ids: HashMap<TypeId, usize>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ids are handed out in registration order, so they stay stable between runs
+    pub fn register<T: 'static>(&mut self) -> usize {
+        let type_id = TypeId::of::<T>();
+        if let Some(id) = self.ids.get(&type_id) {
+            return *id;
+        }
+
+        let id = self.components.len();
+        self.components.push(ComponentInfo {
+            id,
+            type_id,
+            name: std::any::type_name::<T>(),
+            size: std::mem::size_of::<T>(),
+        });
+        self.ids.insert(type_id, id);
+        id
+    }
+
+    pub fn id<T: 'static>(&self) -> Option<usize> {
+        self.id_of(TypeId::of::<T>())
+    }
+
+    pub fn id_of(&self, type_id: TypeId) -> Option<usize> {
+        self.ids.get(&type_id).copied()
+    }
+
+    pub fn info(&self, id: usize) -> Option<&ComponentInfo> {
+        self.components.get(id)
+    }
+
+    pub fn info_of<T: 'static>(&self) -> Option<&ComponentInfo> {
+        self.info(self.id::<T>()?)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ComponentInfo> {
+        self.components.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
@@ -0,0 +1,43 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+#[derive(Default)]
+pub struct Resources {
+    resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.resources
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|old| *old.downcast::<T>().unwrap())
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.resources
+            .remove(&TypeId::of::<T>())
+            .map(|old| *old.downcast::<T>().unwrap())
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.resources
+            .get(&TypeId::of::<T>())
+            .and_then(|item| item.downcast_ref::<T>())
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|item| item.downcast_mut::<T>())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.resources.contains_key(&TypeId::of::<T>())
+    }
+}
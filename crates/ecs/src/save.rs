@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::Entity;
+
+// components that opt into serde are saved as themselves, the rest are skipped
+pub trait SaveComponent: Sized {
+    type Saved: Serialize + DeserializeOwned;
+
+    fn save(&self) -> Option<Self::Saved> {
+        None
+    }
+
+    fn load(_saved: Self::Saved) -> Option<Self> {
+        None
+    }
+
+    // rewrites entity references after a load
+    fn map_entities(&mut self, _map: &EntityMap) {}
+}
+
+// saved entities to the entities created for them on load
+#[derive(Default)]
+pub struct EntityMap {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, saved: Entity, loaded: Entity) {
+        self.entities.insert(saved, loaded);
+    }
+
+    pub fn get(&self, saved: Entity) -> Option<Entity> {
+        self.entities.get(&saved).copied()
+    }
+
+    // references to entities that were not saved become placeholders
+    pub fn map(&self, saved: Entity) -> Entity {
+        self.get(saved).unwrap_or(Entity::PLACEHOLDER)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
@@ -0,0 +1,171 @@
+use crate::Entity;
+
+// world ticks at which a component was inserted and last borrowed mutably
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComponentTicks {
+    pub added: u32,
+    pub changed: u32,
+}
+
+impl ComponentTicks {
+    pub fn new(tick: u32) -> Self {
+        Self {
+            added: tick,
+            changed: tick,
+        }
+    }
+
+    pub fn is_added(&self, last_tick: u32) -> bool {
+        self.added > last_tick
+    }
+
+    pub fn is_changed(&self, last_tick: u32) -> bool {
+        self.changed > last_tick
+    }
+}
+
+#[derive(Clone)]
+pub struct SparseSet<T> {
+    sparse: Vec<Option<usize>>,
+    entities: Vec<Entity>,
+    data: Vec<T>,
+    ticks: Vec<ComponentTicks>,
+}
+
+impl<T> SparseSet<T> {
+    pub fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            entities: Vec::new(),
+            data: Vec::new(),
+            ticks: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    pub(crate) fn dense_index(&self, entity: Entity) -> Option<usize> {
+        let dense = (*self.sparse.get(entity.index())?)?;
+        if self.entities[dense] == entity {
+            Some(dense)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.dense_index(entity).is_some()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        let dense = self.dense_index(entity)?;
+        Some(&self.data[dense])
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        let dense = self.dense_index(entity)?;
+        Some(&mut self.data[dense])
+    }
+
+    pub fn ticks(&self, entity: Entity) -> Option<ComponentTicks> {
+        let dense = self.dense_index(entity)?;
+        Some(self.ticks[dense])
+    }
+
+    pub fn set_changed(&mut self, entity: Entity, tick: u32) {
+        if let Some(dense) = self.dense_index(entity) {
+            self.ticks[dense].changed = tick;
+        }
+    }
+
+    // marks the component as newly inserted
+    pub fn set_added(&mut self, entity: Entity, tick: u32) {
+        if let Some(dense) = self.dense_index(entity) {
+            self.ticks[dense] = ComponentTicks::new(tick);
+        }
+    }
+
+    // inserted without a tick, change filters never see it
+    pub fn insert(&mut self, entity: Entity, value: T) -> Option<T> {
+        self.insert_at(entity, value, 0)
+    }
+
+    // replaces and returns the previous value if the entity already had one
+    pub fn insert_at(&mut self, entity: Entity, value: T, tick: u32) -> Option<T> {
+        if let Some(dense) = self.dense_index(entity) {
+            self.ticks[dense].changed = tick;
+            return Some(std::mem::replace(&mut self.data[dense], value));
+        }
+
+        let index = entity.index();
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, None);
+        }
+
+        // a stale handle may still occupy the slot
+        if let Some(dense) = self.sparse[index] {
+            self.swap_remove(dense);
+        }
+
+        self.sparse[index] = Some(self.data.len());
+        self.entities.push(entity);
+        self.data.push(value);
+        self.ticks.push(ComponentTicks::new(tick));
+        None
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let dense = self.dense_index(entity)?;
+        Some(self.swap_remove(dense))
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(Entity) -> bool) {
+        for dense in (0..self.entities.len()).rev() {
+            if !keep(self.entities[dense]) {
+                self.swap_remove(dense);
+            }
+        }
+    }
+
+    fn swap_remove(&mut self, dense: usize) -> T {
+        let removed = self.entities.swap_remove(dense);
+        self.sparse[removed.index()] = None;
+        if let Some(moved) = self.entities.get(dense) {
+            self.sparse[moved.index()] = Some(dense);
+        }
+        self.ticks.swap_remove(dense);
+        self.data.swap_remove(dense)
+    }
+
+    pub(crate) fn data_ptr(&mut self) -> *mut T {
+        self.data.as_mut_ptr()
+    }
+
+    pub(crate) fn ticks_ptr(&mut self) -> *mut ComponentTicks {
+        self.ticks.as_mut_ptr()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.entities.iter().copied().zip(self.data.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.entities.iter().copied().zip(self.data.iter_mut())
+    }
+}
+
+impl<T> Default for SparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
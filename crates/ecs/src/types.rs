@@ -1,5 +1,30 @@
+use crate::{Entity, SparseSet};
+
 pub trait Component {
-    fn get_id() -> usize;
-    fn entity(&self) -> usize;
+    fn entity(&self) -> Entity;
     fn one_frame(&self) -> bool;
 }
+
+// on_add runs once the component is stored, on_remove while it is still readable
+pub trait ComponentHooks<W> {
+    fn on_add(_world: &mut W, _entity: Entity) {}
+    fn on_remove(_world: &mut W, _entity: Entity) {}
+}
+
+pub trait ComponentClone: Sized {
+    fn clone_component(&self) -> Option<Self> {
+        None
+    }
+}
+
+// rollback-safe components are copied into world snapshots, the rest keep their current state
+pub trait Rollback: Sized {
+    fn snapshot(_storage: &SparseSet<Self>) -> Option<SparseSet<Self>> {
+        None
+    }
+}
+
+// components and groups of them that can be inserted on an entity in one go
+pub trait Bundle<W> {
+    fn insert(self, world: &mut W, entity: Entity);
+}
@@ -1,20 +1,85 @@
 use ecs::init_ecs;
 
-use ecs::Component;
+use ecs::{Added, Changed, Component, Dyn, DynMut, Entity, Name, Prefab, SystemTicks, Value};
 
-use component::component;
+use component::{bundle, component};
 
-#[component]
+#[component(clone, serde, rollback)]
 struct Person {
     name: String,
 }
 
-#[component]
+#[component(serde)]
 struct Greeter {
     greeting: String,
 }
 
-init_ecs!{Person, Greeter}
+#[component(serde, rollback)]
+struct Friends {
+    best: Option<Entity>,
+    others: Vec<Entity>,
+    #[reflect(setter = set_count)]
+    count: u32,
+    #[reflect(read_only)]
+    checked: bool,
+    #[reflect(skip)]
+    secret: f32,
+}
+
+impl Friends {
+    fn set_count(&mut self, count: u32) {
+        self.count = count;
+        self.checked = false;
+    }
+}
+
+// keeps a guest list resource in sync
+#[component(on_add = guest_arrived, on_remove = guest_left)]
+struct Guest {}
+
+fn guest_arrived(world: &mut World, entity: Entity) {
+    world.resource_mut::<Vec<Entity>>().unwrap().push(entity);
+}
+
+fn guest_left(world: &mut World, entity: Entity) {
+    assert!(world.get_guest(entity).is_some());
+    world.resource_mut::<Vec<Entity>>().unwrap().retain(|e| *e != entity);
+}
+
+// a component from another crate, never listed in init_ecs!
+mod particles {
+    pub struct Particle {
+        pub life: f32,
+    }
+}
+
+use particles::Particle;
+
+#[bundle]
+struct Host {
+    person: Person,
+    greeter: Greeter,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Vip;
+
+init_ecs!{Person, Greeter, Guest, Friends; tags: Vip}
+
+// a caller with its own `Name` type next to the macro
+mod labels {
+    use component::component;
+    use ecs::{init_ecs, Component};
+
+    pub struct Name(pub &'static str);
+
+    #[component]
+    pub struct Label {
+        pub text: String,
+    }
+
+    init_ecs!{Label}
+}
 
 #[test]
 fn test_query() {
@@ -35,4 +100,926 @@ fn test_query() {
     }.add(world);
 
     assert_eq!(1, world.query().person().greeter().fetch().len());
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_query_filters() {
+    let world = &mut World::create();
+    let mut people = Vec::new();
+    for i in 0..3 {
+        let e = world.create_entity();
+        Person {
+            entity: e,
+            one_frame: false,
+            name: format!("{}", i),
+        }.add(world);
+        people.push(e);
+    }
+    let greeter = world.create_entity();
+    Greeter {
+        entity: greeter,
+        one_frame: false,
+        greeting: "Hello".to_string(),
+    }.add(world);
+    Greeter {
+        entity: people[0],
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    }.add(world);
+
+    assert_eq!(vec![people[1], people[2]], world.query().person().without_greeter().fetch());
+    assert_eq!(Some(greeter), world.query().greeter().without_person().single());
+    assert_eq!(4, world.query().person().or().greeter().count());
+    assert_eq!(3, world.query().without_person().or().person().without_greeter().count());
+    assert!(world.query().person().greeter().any());
+    assert!(!world.query().greeter().without_greeter().any());
+    assert_eq!(None, world.query().person().single());
+}
+
+#[test]
+fn test_destroy_entity() {
+    let world = &mut World::create();
+    let e = world.create_entity();
+    Person {
+        entity: e,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+    Greeter {
+        entity: e,
+        one_frame: false,
+        greeting: "Hello".to_string(),
+    }.add(world);
+
+    assert!(world.destroy_entity(e));
+    assert!(!world.destroy_entity(e));
+    assert!(!world.is_alive(e));
+    assert_eq!((0, 0), world.stat());
+    assert_eq!(0, world.query().person().fetch().len());
+}
+
+#[test]
+fn test_stale_entity() {
+    let world = &mut World::create();
+    let old = world.create_entity();
+    world.destroy_entity(old);
+
+    let new = world.create_entity();
+    assert_eq!(old.index(), new.index());
+    assert_ne!(old, new);
+
+    Person {
+        entity: new,
+        one_frame: false,
+        name: "Jerry".to_string(),
+    }.add(world);
+
+    assert!(world.get_person(old).is_none());
+    assert_eq!("Jerry", world.get_person(new).unwrap().name);
+
+    Person {
+        entity: old,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+
+    assert_eq!(1, world.stat().1);
+}
+
+#[test]
+fn test_sparse_storage() {
+    let world = &mut World::create();
+    let entities: Vec<_> = (0..5).map(|_| world.create_entity()).collect();
+    for (i, e) in entities.iter().enumerate() {
+        Person {
+            entity: *e,
+            one_frame: false,
+            name: format!("{}", i),
+        }.add(world);
+    }
+
+    Person {
+        entity: entities[1],
+        one_frame: false,
+        name: String::new(),
+    }.remove(world);
+    assert!(world.get_person(entities[1]).is_none());
+    for i in [0, 2, 3, 4] {
+        assert_eq!(format!("{}", i), world.get_person(entities[i]).unwrap().name);
+    }
+
+    Person {
+        entity: entities[2],
+        one_frame: false,
+        name: "replaced".to_string(),
+    }.add(world);
+
+    assert_eq!(4, world.stat().1);
+    assert_eq!("replaced", world.get_person(entities[2]).unwrap().name);
+
+    Greeter {
+        entity: entities[3],
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    }.add(world);
+
+    assert_eq!(vec![entities[3]], world.query().person().greeter().fetch());
+}
+
+#[test]
+fn test_get_mut() {
+    let world = &mut World::create();
+    let e = world.create_entity();
+    Person {
+        entity: e,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+
+    world.get_person_mut(e).unwrap().name.push_str("my");
+    world.get_mut::<Person>(e).unwrap().name.push('!');
+
+    assert_eq!("Tommy!", world.get_person(e).unwrap().name);
+    assert_eq!("Tommy!", world.get::<Person>(e).unwrap().name);
+    assert!(world.get::<Greeter>(e).is_none());
+}
+
+#[test]
+fn test_query_mut() {
+    let world = &mut World::create();
+    for i in 0..3 {
+        let e = world.create_entity();
+        Person {
+            entity: e,
+            one_frame: false,
+            name: format!("{}", i),
+        }.add(world);
+        if i != 1 {
+            Greeter {
+                entity: e,
+                one_frame: false,
+                greeting: "Hello".to_string(),
+            }.add(world);
+        }
+    }
+
+    let mut count = 0;
+    for (greeter, person) in world.query_mut::<(&Greeter, &mut Person)>() {
+        person.name = format!("{} {}", greeter.greeting, person.name);
+        count += 1;
+    }
+    assert_eq!(2, count);
+
+    let mut names: Vec<String> = world.query_mut::<(&Person,)>().map(|(p,)| p.name.clone()).collect();
+    names.sort();
+    assert_eq!(vec!["1", "Hello 0", "Hello 2"], names);
+}
+
+#[test]
+#[should_panic]
+fn test_query_mut_aliasing() {
+    let world = &mut World::create();
+    world.query_mut::<(&Person, &mut Person)>().count();
+}
+
+#[test]
+fn test_query_optional() {
+    let world = &mut World::create();
+    let tom = world.create_entity();
+    Person {
+        entity: tom,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+
+    let jerry = world.create_entity();
+    Person {
+        entity: jerry,
+        one_frame: false,
+        name: "Jerry".to_string(),
+    }.add(world);
+    Greeter {
+        entity: jerry,
+        one_frame: false,
+        greeting: "Hello".to_string(),
+    }.add(world);
+
+    for (e, person, greeter) in world.query_ref::<(Entity, &Person, Option<&Greeter>)>() {
+        if e == jerry {
+            assert_eq!("Jerry", person.name);
+            assert_eq!("Hello", greeter.unwrap().greeting);
+        } else {
+            assert_eq!(tom, e);
+            assert!(greeter.is_none());
+        }
+    }
+
+    for (person, greeter) in world.query_mut::<(&mut Person, Option<&mut Greeter>)>() {
+        if let Some(greeter) = greeter {
+            greeter.greeting = format!("{} {}", greeter.greeting, person.name);
+        }
+        person.name.push('!');
+    }
+
+    assert_eq!("Tom!", world.get_person(tom).unwrap().name);
+    assert_eq!("Hello Jerry", world.get_greeter(jerry).unwrap().greeting);
+
+    let empty = world.create_entity();
+    let matched: Vec<Entity> = world.query_ref::<(Entity, Option<&Greeter>)>().map(|(e, _)| e).collect();
+    assert_eq!(3, matched.len());
+    assert!(matched.contains(&empty));
+    assert_eq!(1, world.query_ref::<&Greeter>().count());
+}
+
+#[test]
+fn test_component_registry() {
+    let world = &mut World::create();
+    assert_eq!(Some(0), world.component_id::<Person>());
+    assert_eq!(Some(1), world.component_id::<Greeter>());
+    assert_eq!(None, world.component_id::<String>());
+
+    let info = world.components().info_of::<Greeter>().unwrap();
+    assert_eq!(std::any::TypeId::of::<Greeter>(), info.type_id);
+    assert!(info.name.ends_with("Greeter"));
+    assert_eq!(std::mem::size_of::<Greeter>(), info.size);
+
+    let e = world.create_entity();
+    Greeter {
+        entity: e,
+        one_frame: false,
+        greeting: "Hello".to_string(),
+    }.add(world);
+
+    let names: Vec<&str> = world
+        .components()
+        .iter()
+        .filter(|info| world.has_component(info.id, e))
+        .map(|info| info.name)
+        .collect();
+    assert_eq!(1, names.len());
+    assert!(names[0].ends_with("Greeter"));
+}
+
+struct Score(u32);
+
+#[test]
+fn test_resources() {
+    let world = &mut World::create();
+    assert!(world.resource::<Score>().is_none());
+
+    assert!(world.insert_resource(Score(1)).is_none());
+    world.resource_mut::<Score>().unwrap().0 += 1;
+    assert_eq!(2, world.resource::<Score>().unwrap().0);
+
+    assert_eq!(2, world.insert_resource(Score(10)).unwrap().0);
+    assert_eq!(10, world.remove_resource::<Score>().unwrap().0);
+    assert!(world.resource::<Score>().is_none());
+    assert_eq!((0, 0), world.stat());
+}
+
+#[test]
+fn test_events() {
+    let world = &mut World::create();
+    let mut early = ecs::EventReader::<Score>::default();
+
+    world.send_event(Score(1));
+    world.event_writer().send_batch([Score(2), Score(3)]);
+    let mut late = world.event_reader::<Score>();
+
+    let read: Vec<u32> = world.read_events(&mut early).map(|s| s.0).collect();
+    assert_eq!(vec![1, 2, 3], read);
+    assert_eq!(0, world.read_events(&mut early).count());
+
+    world.update_events();
+    world.send_event(Score(4));
+    let read: Vec<u32> = world.read_events(&mut late).map(|s| s.0).collect();
+    assert_eq!(vec![4], read);
+
+    let mut missed = ecs::EventReader::<Score>::default();
+    world.update_events();
+    world.update_events();
+    assert_eq!(0, world.read_events(&mut missed).count());
+    assert_eq!(0, world.read_events(&mut early).count());
+    assert_eq!((0, 0), world.stat());
+}
+
+#[test]
+fn test_commands() {
+    let world = &mut World::create();
+    let tom = world.create_entity();
+    Person {
+        entity: tom,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+
+    let commands = world.commands();
+    let mut spawned = Vec::new();
+    for person in world.query_ref::<&Person>() {
+        let e = commands.spawn();
+        commands.insert(Greeter {
+            entity: e,
+            one_frame: false,
+            greeting: format!("Hello {}", person.name),
+        });
+        spawned.push(e);
+    }
+    commands.remove::<Person>(tom);
+    assert_eq!(2, commands.len());
+
+    // nothing changes until the sync point
+    assert!(!world.is_alive(spawned[0]));
+    assert!(world.get_person(tom).is_some());
+
+    world.apply_commands();
+    assert!(world.is_alive(spawned[0]));
+    assert_eq!("Hello Tom", world.get_greeter(spawned[0]).unwrap().greeting);
+    assert!(world.get_person(tom).is_none());
+
+    world.commands().despawn(spawned[0]);
+    world.apply_commands();
+    assert!(!world.is_alive(spawned[0]));
+    assert_eq!(0, world.query().greeter().count());
+
+    // reserved indices never collide with entities created afterwards
+    let reserved = world.commands().spawn();
+    let created = world.create_entity();
+    assert_ne!(reserved.index(), created.index());
+    assert!(world.is_alive(reserved));
+}
+
+#[test]
+fn test_commands_during_query_mut() {
+    let world = &mut World::create();
+    let tom = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Tom".to_string(),
+    }).id();
+    let ann = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Ann".to_string(),
+    }).id();
+
+    let queue = world.command_queue();
+    let mut spawned = Vec::new();
+    for (entity, person) in world.query_mut::<(Entity, &mut Person)>() {
+        person.name.push('!');
+        if entity == ann {
+            queue.despawn(entity);
+        }
+        let greeter = queue.spawn();
+        queue.insert(Greeter {
+            entity: greeter,
+            one_frame: false,
+            greeting: format!("Hello {}", person.name),
+        });
+        spawned.push(greeter);
+    }
+    assert_eq!(3, queue.len());
+
+    // the world's own queue is left alone
+    world.apply_commands();
+    assert!(world.is_alive(ann));
+
+    world.apply(queue);
+    assert!(!world.is_alive(ann));
+    assert_eq!("Tom!", world.get_person(tom).unwrap().name);
+    assert_eq!(2, world.query().greeter().count());
+    for greeter in spawned {
+        assert!(world.is_alive(greeter));
+    }
+
+    // reservations through a detached queue don't collide with entities created in between
+    let queue = world.command_queue();
+    let reserved = queue.spawn();
+    let created = world.create_entity();
+    assert_ne!(reserved.index(), created.index());
+    world.apply(queue);
+    assert!(world.is_alive(reserved));
+}
+
+#[test]
+fn test_change_detection() {
+    let world = &mut World::create();
+    let tom = world.create_entity();
+    let ann = world.create_entity();
+    for (e, name) in [(tom, "Tom"), (ann, "Ann")] {
+        Person {
+            entity: e,
+            one_frame: false,
+            name: name.to_string(),
+        }.add(world);
+    }
+
+    assert_eq!(2, world.query_ref::<(Entity, Added<Person>)>().count());
+    assert_eq!(2, world.query_ref::<(Entity, Changed<Person>)>().count());
+
+    world.clear_trackers();
+    assert_eq!(0, world.query_ref::<(Entity, Changed<Person>)>().count());
+
+    world.get_person_mut(ann).unwrap().name = "Anna".to_string();
+    let changed: Vec<_> = world.query_ref::<(Entity, Changed<Person>)>().map(|(e, _)| e).collect();
+    assert_eq!(vec![ann], changed);
+    assert_eq!(0, world.query_ref::<(Entity, Added<Person>)>().count());
+
+    // the filter sees the ticks from before the query borrowed mutably
+    world.clear_trackers();
+    assert_eq!(0, world.query_mut::<(&mut Person, Changed<Person>)>().count());
+    assert_eq!(2, world.query_mut::<&mut Person>().count());
+    assert_eq!(2, world.query_ref::<(Entity, Changed<Person>)>().count());
+
+    world.clear_trackers();
+    Person {
+        entity: tom,
+        one_frame: false,
+        name: "Thomas".to_string(),
+    }.add(world);
+    assert_eq!(0, world.query_ref::<(Entity, Added<Person>)>().count());
+    assert_eq!(1, world.query_ref::<(Entity, Changed<Person>)>().count());
+}
+
+#[test]
+fn test_system_ticks() {
+    let world = &mut World::create();
+    let tom = world.create_entity();
+    Person {
+        entity: tom,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+
+    // `batch` runs first in the frame, `rename` after it
+    world.begin_system(0);
+    assert_eq!(1, world.query_ref::<(Entity, Changed<Person>)>().count());
+    let batch = world.end_system();
+
+    world.begin_system(0);
+    world.get_person_mut(tom).unwrap().name = "Thomas".to_string();
+    let rename = world.end_system();
+    world.clear_trackers();
+
+    // the change made after `batch` ran is still seen on its next run, but not by `rename` itself
+    world.begin_system(batch);
+    assert_eq!(1, world.query_ref::<(Entity, Changed<Person>)>().count());
+    let batch = world.end_system();
+
+    world.begin_system(rename);
+    assert_eq!(0, world.query_ref::<(Entity, Changed<Person>)>().count());
+    world.end_system();
+
+    world.begin_system(batch);
+    assert_eq!(0, world.query_ref::<(Entity, Changed<Person>)>().count());
+    world.end_system();
+}
+
+#[test]
+fn test_component_hooks() {
+    let world = &mut World::create();
+    world.insert_resource(Vec::<Entity>::new());
+    let guests: Vec<_> = (0..3).map(|_| world.create_entity()).collect();
+    for e in guests.iter() {
+        Guest {
+            entity: *e,
+            one_frame: false,
+        }.add(world);
+    }
+    assert_eq!(&guests, world.resource::<Vec<Entity>>().unwrap());
+
+    // replacing runs on_remove before on_add
+    Guest {
+        entity: guests[0],
+        one_frame: false,
+    }.add(world);
+    assert_eq!(3, world.resource::<Vec<Entity>>().unwrap().len());
+
+    world.remove_component::<Guest>(guests[0]);
+    world.destroy_entity(guests[1]);
+    world.commands().remove::<Guest>(guests[2]);
+    world.apply_commands();
+    assert!(world.resource::<Vec<Entity>>().unwrap().is_empty());
+}
+
+#[test]
+fn test_spawn_builder() {
+    let world = &mut World::create();
+    world.insert_resource(Vec::<Entity>::new());
+    let host = Host {
+        person: Person {
+            entity: Entity::default(),
+            one_frame: false,
+            name: "Tom".to_string(),
+        },
+        greeter: Greeter {
+            entity: Entity::default(),
+            one_frame: false,
+            greeting: "Hello".to_string(),
+        },
+    };
+    let e = world
+        .spawn()
+        .with(host)
+        .with(Guest {
+            entity: Entity::default(),
+            one_frame: false,
+        })
+        .id();
+
+    assert!(world.is_alive(e));
+    assert_eq!(e, world.get_person(e).unwrap().entity);
+    assert_eq!("Hello", world.get_greeter(e).unwrap().greeting);
+    assert_eq!(&vec![e], world.resource::<Vec<Entity>>().unwrap());
+    assert!(!world.is_alive(Entity::default()));
+}
+
+#[test]
+fn test_clone_entity() {
+    let world = &mut World::create();
+    let e = world.create_entity();
+    Person {
+        entity: e,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+    Greeter {
+        entity: e,
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    }.add(world);
+
+    // greeters did not opt into cloning
+    let clone = world.clone_entity(e).unwrap();
+    assert_ne!(e, clone);
+    assert_eq!(clone, world.get_person(clone).unwrap().entity);
+    assert_eq!("Tom", world.get_person(clone).unwrap().name);
+    assert!(world.get_greeter(clone).is_none());
+
+    world.destroy_entity(e);
+    assert!(world.clone_entity(e).is_none());
+}
+
+#[test]
+fn test_prefab() {
+    let world = &mut World::create();
+    let prefab = Prefab::new().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Prop".to_string(),
+    });
+
+    let plain = world.instantiate(&prefab).id();
+    let named = world
+        .instantiate(&prefab)
+        .with(Person {
+            entity: Entity::default(),
+            one_frame: false,
+            name: "Tom".to_string(),
+        })
+        .id();
+
+    assert_eq!("Prop", world.get_person(plain).unwrap().name);
+    assert_eq!("Tom", world.get_person(named).unwrap().name);
+
+    let from_entity = world.create_prefab(named);
+    assert_eq!(1, from_entity.len());
+    let copy = world.instantiate(&from_entity).id();
+    assert_eq!("Tom", world.get_person(copy).unwrap().name);
+    assert_eq!(3, world.query().person().count());
+}
+
+#[test]
+fn test_save_load() {
+    let world = &mut World::create();
+    world.insert_resource(Vec::<Entity>::new());
+    let tom = world.create_entity();
+    let ann = world.create_entity();
+    Person {
+        entity: tom,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+    Person {
+        entity: ann,
+        one_frame: false,
+        name: "Ann".to_string(),
+    }.add(world);
+    Friends {
+        entity: tom,
+        one_frame: false,
+        best: Some(ann),
+        others: vec![tom],
+        count: 2,
+        checked: true,
+        secret: 0.0,
+    }.add(world);
+    // guests are not serializable
+    Guest {
+        entity: world.create_entity(),
+        one_frame: false,
+    }.add(world);
+
+    let ron = world.save_ron().unwrap();
+    let json = world.save_json().unwrap();
+    assert_eq!(2, world.save().entities.len());
+
+    for (format, data) in [("ron", &ron), ("json", &json)] {
+        let loaded = &mut World::create();
+        loaded.create_entity();
+        let map = match format {
+            "ron" => loaded.load_ron(data).unwrap(),
+            _ => loaded.load_json(data).unwrap(),
+        };
+        assert_eq!(2, map.len());
+        assert_eq!(2, loaded.query().person().count());
+        assert_eq!(0, loaded.query().guest().count());
+
+        let (new_tom, new_ann) = (map.map(tom), map.map(ann));
+        assert_ne!(tom, new_tom);
+        assert_eq!("Ann", loaded.get_person(new_ann).unwrap().name);
+        let friends = loaded.get_friends(new_tom).unwrap();
+        assert_eq!(new_tom, friends.entity);
+        assert_eq!(Some(new_ann), friends.best);
+        assert_eq!(vec![new_tom], friends.others);
+    }
+}
+
+#[test]
+fn test_reflection() {
+    let world = &mut World::create();
+    let e = world.create_entity();
+    Person {
+        entity: e,
+        one_frame: false,
+        name: "Tom".to_string(),
+    }.add(world);
+    Friends {
+        entity: e,
+        one_frame: false,
+        best: None,
+        others: Vec::new(),
+        count: 0,
+        checked: true,
+        secret: 1.0,
+    }.add(world);
+
+    let components = world.reflect(e);
+    assert_eq!(2, components.len());
+    assert_eq!("Person", components[0].1.type_name());
+    assert_eq!(Some(Value::String("Tom".to_string())), components[0].1.field("name"));
+
+    let id = world.component_id::<Friends>().unwrap();
+    let friends = world.reflect_component(id, e).unwrap();
+    let fields: Vec<_> = friends.fields().iter().map(|f| (f.name, f.type_name, f.editable)).collect();
+    assert_eq!(
+        vec![
+            ("best", "Option<Entity>", false),
+            ("others", "Vec<Entity>", false),
+            ("count", "u32", true),
+            ("checked", "bool", false),
+        ],
+        fields
+    );
+    assert_eq!(None, friends.field("best"));
+
+    world.clear_trackers();
+    let friends = world.reflect_component_mut(id, e).unwrap();
+    assert!(friends.set_field("count", Value::U32(5)));
+    assert!(!friends.set_field("count", Value::F32(5.0)));
+    assert!(!friends.set_field("checked", Value::Bool(true)));
+    assert!(!friends.set_field("secret", Value::F32(2.0)));
+    assert_eq!(5, world.get_friends(e).unwrap().count);
+    assert!(!world.get_friends(e).unwrap().checked);
+    assert_eq!(1, world.query_ref::<(Entity, Changed<Friends>)>().count());
+}
+
+#[test]
+fn test_names() {
+    let world = &mut World::create();
+    let aircraft = world.spawn().with(Name::new("aircraft")).id();
+    let hangar = world.spawn().with(Name::new("hangar")).id();
+
+    assert_eq!(Some(aircraft), world.find_by_name("aircraft"));
+    assert_eq!(Some(hangar), world.find_by_name("hangar"));
+    assert_eq!(None, world.find_by_name("tower"));
+
+    // renaming replaces the component
+    world.spawn();
+    let mut name = Name::new("tower");
+    name.entity = hangar;
+    name.add(world);
+    assert_eq!(None, world.find_by_name("hangar"));
+    assert_eq!(Some(hangar), world.find_by_name("tower"));
+
+    let copy = world.clone_entity(aircraft).unwrap();
+    assert_eq!(vec![aircraft, copy], world.find_all_by_name("aircraft"));
+
+    world.destroy_entity(aircraft);
+    assert_eq!(Some(copy), world.find_by_name("aircraft"));
+    world.commands().despawn(copy);
+    world.apply_commands();
+    assert_eq!(None, world.find_by_name("aircraft"));
+
+    // a name changed in place is not found under the old one, also after a despawn
+    let tower = world.spawn().with(Name::new("a")).id();
+    for name in world.query_mut::<&mut Name>() {
+        if name.as_str() == "a" {
+            *name = Name::new("b");
+        }
+    }
+    assert_eq!(None, world.find_by_name("a"));
+    world.destroy_entity(tower);
+    let other = world.spawn().with(Name::new("a")).id();
+    assert_eq!(vec![other], world.find_all_by_name("a"));
+    world.destroy_entity(other);
+
+    // the index is rebuilt on load
+    let saved = world.save_json().unwrap();
+    let loaded = &mut World::create();
+    let map = loaded.load_json(&saved).unwrap();
+    assert_eq!(Some(map.map(hangar)), loaded.find_by_name("tower"));
+}
+
+#[test]
+fn test_tags() {
+    assert_eq!(0, std::mem::size_of::<Vip>());
+
+    let world = &mut World::create();
+    let tom = world.spawn().with(Vip).id();
+    let ann = world.create_entity();
+    Person {
+        entity: ann,
+        one_frame: false,
+        name: "Ann".to_string(),
+    }.add(world);
+
+    assert!(world.has_tag::<Vip>(tom));
+    assert!(!world.has_tag::<Vip>(ann));
+    assert_eq!(vec![tom], world.query().vip().fetch());
+    assert_eq!(vec![ann], world.query().person().without_vip().fetch());
+    assert_eq!(1, world.query_ref::<(Entity, &Vip)>().count());
+
+    world.add_tag(ann, Vip);
+    let clone = world.clone_entity(ann).unwrap();
+    assert!(world.has_tag::<Vip>(clone));
+    assert!(world.remove_tag::<Vip>(ann));
+    assert!(!world.remove_tag::<Vip>(ann));
+
+    world.destroy_entity(tom);
+    assert_eq!(vec![clone], world.query().vip().fetch());
+}
+
+#[test]
+fn test_snapshot_restore() {
+    let world = &mut World::create();
+    let tom = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Tom".to_string(),
+    }).with(Vip).id();
+    let ann = world.spawn().with(Name::new("ann")).id();
+    Friends {
+        entity: ann,
+        one_frame: false,
+        best: Some(tom),
+        others: vec![tom],
+        count: 1,
+        checked: true,
+        secret: 0.0,
+    }.add(world);
+
+    let before = world.save_json().unwrap();
+    let snapshot = world.snapshot();
+
+    for person in world.query_mut::<&mut Person>() {
+        person.name = "Bob".to_string();
+    }
+    world.destroy_entity(ann);
+    let bob = world.spawn().with(Name::new("bob")).with(Vip).id();
+    Greeter {
+        entity: bob,
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    }.add(world);
+    world.remove_tag::<Vip>(tom);
+
+    world.restore(&snapshot);
+
+    assert_eq!(before, world.save_json().unwrap());
+    assert!(world.is_alive(ann));
+    assert!(!world.is_alive(bob));
+    assert!(world.get_greeter(bob).is_none());
+    assert_eq!(Some(ann), world.find_by_name("ann"));
+    assert_eq!(None, world.find_by_name("bob"));
+    assert_eq!(vec![tom], world.query().vip().fetch());
+
+    // entities are handed out again in the same order
+    let again = world.create_entity();
+    world.restore(&snapshot);
+    assert_eq!(again, world.create_entity());
+
+    // a spawn still queued keeps its index
+    world.restore(&snapshot);
+    world.create_entity();
+    let reserved = world.commands().spawn();
+    world.restore(&snapshot);
+    let fresh = world.create_entity();
+    world.apply_commands();
+    assert_ne!(reserved, fresh);
+    assert!(world.is_alive(reserved));
+    assert!(world.is_alive(fresh));
+}
+
+#[test]
+fn test_restore_hooks_and_ticks() {
+    let world = &mut World::create();
+    world.insert_resource(Vec::<Entity>::new());
+    let tom = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Tom".to_string(),
+    }).id();
+    let ann = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Ann".to_string(),
+    }).with(Greeter {
+        entity: Entity::default(),
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    }).id();
+    let snapshot = world.snapshot();
+
+    world.destroy_entity(ann);
+    let bob = world.spawn().with(Guest {
+        entity: Entity::default(),
+        one_frame: false,
+    }).id();
+    assert_eq!(&vec![bob], world.resource::<Vec<Entity>>().unwrap());
+    world.clear_trackers();
+
+    // bob's guest component goes through `on_remove`, ann comes back without her greeter
+    assert_eq!(vec![ann], world.restore(&snapshot));
+    assert!(world.resource::<Vec<Entity>>().unwrap().is_empty());
+    assert_eq!("Ann", world.get_person(ann).unwrap().name);
+    assert!(world.get_greeter(ann).is_none());
+
+    let changed: Vec<_> = world.query_ref::<(Entity, Changed<Person>)>().map(|(e, _)| e).collect();
+    let added: Vec<_> = world.query_ref::<(Entity, Added<Person>)>().map(|(e, _)| e).collect();
+    assert_eq!(vec![tom, ann], changed);
+    assert_eq!(vec![ann], added);
+}
+
+#[test]
+fn test_dynamic_components() {
+    let world = &mut World::create();
+    let tom = world.spawn().with(Person {
+        entity: Entity::default(),
+        one_frame: false,
+        name: "Tom".to_string(),
+    }).id();
+    let ann = world.create_entity();
+
+    assert_eq!(0, world.query_ref::<Dyn<Particle>>().count());
+    assert!(world.get_dynamic::<Particle>(tom).is_none());
+
+    assert!(matches!(world.insert_dynamic(tom, Particle { life: 1.0 }), Ok(None)));
+    assert!(world.insert_dynamic(ann, Particle { life: 2.0 }).is_ok());
+    let id = world.component_id::<Particle>().unwrap();
+    assert_eq!(id, world.register_component::<Particle>());
+    assert_eq!(world.component_id::<Person>(), Some(world.register_component::<Person>()));
+    assert!(world.has_component(id, tom));
+
+    for (person, particle) in world.query_mut::<(&Person, DynMut<Particle>)>() {
+        assert_eq!("Tom", person.name);
+        particle.life -= 0.5;
+    }
+    assert_eq!(0.5, world.get_dynamic::<Particle>(tom).unwrap().life);
+    assert_eq!(2.0, world.get_dynamic::<Particle>(ann).unwrap().life);
+    assert_eq!(vec![ann], world.query().with::<Particle>().without::<Person>().fetch());
+
+    world.get_dynamic_mut::<Particle>(ann).unwrap().life = 3.0;
+    assert_eq!(3.0, world.remove_dynamic::<Particle>(ann).unwrap().life);
+    world.destroy_entity(tom);
+    assert!(world.dynamic_storage::<Particle>().unwrap().is_empty());
+
+    // dead entities and components listed in init_ecs! get the value back
+    let life = world.insert_dynamic(tom, Particle { life: 4.0 }).err().unwrap().life;
+    assert_eq!(4.0, life);
+    assert!(world.dynamic_storage::<Particle>().unwrap().is_empty());
+    let greeter = Greeter {
+        entity: ann,
+        one_frame: false,
+        greeting: "Hi".to_string(),
+    };
+    assert_eq!("Hi", world.insert_dynamic(ann, greeter).err().unwrap().greeting);
+    assert!(world.get_greeter(ann).is_none());
+}
+
+#[test]
+fn test_own_name_type() {
+    let world = &mut labels::World::create();
+    let own = labels::Name("label");
+    let e = world.spawn().with(Name::new(own.0)).id();
+    assert_eq!(Some(e), world.find_by_name("label"));
+}
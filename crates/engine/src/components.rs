@@ -1,19 +1,23 @@
 mod camera;
 mod dir_light;
+mod hierarchy;
 mod mesh;
+mod mesh_asset;
 mod package;
 mod point_light;
-mod scenedata;
+mod render_layers;
 mod skybox;
 mod spotlight;
 mod transform;
 
-pub use camera::Camera;
+pub use camera::{Camera, ClearMode, Projection, Viewport};
 pub use dir_light::DirectionalLight;
-pub use mesh::Mesh;
+pub use hierarchy::{Children, GlobalTransform, Parent};
+pub use mesh::{Mesh, MeshBundle};
+pub use mesh_asset::{MaterialAsset, MeshAsset, MeshAssetCache};
 pub use package::DrawPackage;
 pub use point_light::PointLight;
-pub use scenedata::SceneData;
+pub use render_layers::RenderLayers;
 pub use skybox::Skybox;
 pub use spotlight::SpotLight;
 pub use transform::Transform;
@@ -1,30 +1,105 @@
-use ecs::Component;
+use std::rc::Rc;
+
+use ecs::{Component, Entity};
 
 use component::component;
-use math::Matrix4x4;
+use math::{Color, Matrix4x4};
+use webgl::RenderTarget;
+
+#[derive(Clone, Copy, Debug, PartialEq, ecs::serde::Serialize, ecs::serde::Deserialize)]
+#[serde(crate = "ecs::serde")]
+pub enum Projection {
+    // vertical field of view in degrees
+    Perspective { fov: f32 },
+    // half of the visible height in world units
+    Orthographic { size: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ecs::serde::Serialize, ecs::serde::Deserialize)]
+#[serde(crate = "ecs::serde")]
+pub enum ClearMode {
+    ColorAndDepth,
+    DepthOnly,
+    Nothing,
+}
+
+// part of the screen in 0..1, from the bottom left corner
+#[derive(Clone, Copy, Debug, PartialEq, ecs::serde::Serialize, ecs::serde::Deserialize)]
+#[serde(crate = "ecs::serde")]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
 
-#[component]
+impl Viewport {
+    pub const FULL: Viewport = Viewport { x: 0.0, y: 0.0, width: 1.0, height: 1.0 };
+
+    // (x, y, width, height) in pixels
+    pub fn pixels(&self, resolution: (i32, i32)) -> (i32, i32, i32, i32) {
+        let (w, h) = (resolution.0 as f32, resolution.1 as f32);
+        (
+            (self.x * w) as i32,
+            (self.y * h) as i32,
+            ((self.width * w) as i32).max(1),
+            ((self.height * h) as i32).max(1),
+        )
+    }
+}
+
+#[component(clone, serde)]
 pub struct Camera {
-    fov: f32,
+    projection: Projection,
     near: f32,
     far: f32,
+    viewport: Viewport,
+    clear_mode: ClearMode,
+    clear_color: Color,
+    // cameras render from the lowest order up, later ones draw on top
+    order: i32,
+    // drawn are meshes and skyboxes whose `RenderLayers` share a bit with the mask
+    culling_mask: u32,
+    // renders offscreen instead of to the canvas, the viewport is then relative to the target
+    #[serde(skip)]
+    target: Option<Rc<RenderTarget>>,
     view_matrix: Matrix4x4,
 }
 
 impl Camera {
-    pub fn new(entity: usize) -> Self {
+    pub fn new(entity: Entity) -> Self {
         Self {
             entity,
             one_frame: false,
-            fov: 45.0,
+            projection: Projection::Perspective { fov: 45.0 },
             near: 0.1,
             far: 1000.0,
+            viewport: Viewport::FULL,
+            clear_mode: ClearMode::ColorAndDepth,
+            clear_color: Color::new(0.152, 0.214, 0.3),
+            order: 0,
+            culling_mask: u32::MAX,
+            target: None,
             view_matrix: Matrix4x4::identity(),
         }
     }
-    
-    pub fn fov(&self) -> f32 {
-        self.fov
+
+    pub fn orthographic(entity: Entity, size: f32) -> Self {
+        let mut camera = Self::new(entity);
+        camera.projection = Projection::Orthographic { size };
+        camera
+    }
+
+    // `None` for orthographic cameras
+    pub fn fov(&self) -> Option<f32> {
+        match self.projection {
+            Projection::Perspective { fov } => Some(fov),
+            Projection::Orthographic { .. } => None,
+        }
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
     }
 
     pub fn near(&self) -> f32 {
@@ -35,6 +110,86 @@ impl Camera {
         self.far
     }
 
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    pub fn clear_mode(&self) -> ClearMode {
+        self.clear_mode
+    }
+
+    pub fn clear_color(&self) -> Color {
+        self.clear_color
+    }
+
+    pub fn order(&self) -> i32 {
+        self.order
+    }
+
+    pub fn culling_mask(&self) -> u32 {
+        self.culling_mask
+    }
+
+    pub fn target(&self) -> Option<&Rc<RenderTarget>> {
+        self.target.as_ref()
+    }
+
+    pub fn set_projection(&mut self, value: Projection) {
+        self.projection = value;
+    }
+
+    pub fn set_near(&mut self, value: f32) {
+        self.near = value;
+    }
+
+    pub fn set_far(&mut self, value: f32) {
+        self.far = value;
+    }
+
+    pub fn set_viewport(&mut self, value: Viewport) {
+        self.viewport = value;
+    }
+
+    pub fn set_clear_mode(&mut self, value: ClearMode) {
+        self.clear_mode = value;
+    }
+
+    pub fn set_clear_color(&mut self, value: Color) {
+        self.clear_color = value;
+    }
+
+    pub fn set_order(&mut self, value: i32) {
+        self.order = value;
+    }
+
+    pub fn set_culling_mask(&mut self, value: u32) {
+        self.culling_mask = value;
+    }
+
+    pub fn set_target(&mut self, value: Option<Rc<RenderTarget>>) {
+        self.target = value;
+    }
+
+    pub fn sees(&self, layers: u32) -> bool {
+        self.culling_mask & layers != 0
+    }
+
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4x4 {
+        match self.projection {
+            Projection::Perspective { fov } => {
+                Matrix4x4::new_perspective(aspect, fov.to_radians(), self.near, self.far)
+            }
+            Projection::Orthographic { size } => Matrix4x4::new_orthographic(
+                -size * aspect,
+                size * aspect,
+                -size,
+                size,
+                self.near,
+                self.far,
+            ),
+        }
+    }
+
     pub fn get_view_matrix(&self) -> Matrix4x4 {
         self.view_matrix
     }
@@ -42,4 +197,10 @@ impl Camera {
     pub fn set_view_matrix(&mut self, value: Matrix4x4) {
         self.view_matrix = value;
     }
-}
\ No newline at end of file
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(Entity::default())
+    }
+}
@@ -0,0 +1,106 @@
+use ecs::{Component, Entity};
+
+use component::component;
+
+use math::{Matrix3d, Matrix4d, Matrix4x4, Vector3d};
+
+use crate::components::Transform;
+
+// `Transform` of a child is relative to its parent, set through `World::set_parent` or by inserting it.
+// Neither side is cloned, hooks in `init_world!` keep `Parent` and `Children` in sync
+#[component(serde, rollback, hooks)]
+pub struct Parent {
+    #[reflect(read_only)]
+    pub parent: Entity,
+}
+
+// filled in by the `Parent` hooks, so it isn't saved but rebuilt when the children load
+#[component(rollback, hooks)]
+pub struct Children {
+    #[reflect(read_only)]
+    pub children: Vec<Entity>,
+}
+
+// world-space result of the hierarchy, written by the propagation system before batching
+#[component(clone, rollback)]
+pub struct GlobalTransform {
+    #[reflect(read_only)]
+    position: Vector3d,
+    #[reflect(read_only)]
+    front: Vector3d,
+    #[reflect(read_only)]
+    right: Vector3d,
+    #[reflect(read_only)]
+    up: Vector3d,
+    #[reflect(skip)]
+    matrix: Matrix4x4,
+}
+
+impl GlobalTransform {
+    pub fn from_transform(transform: &Transform) -> Self {
+        Self {
+            entity: transform.entity,
+            one_frame: false,
+            position: transform.position(),
+            front: transform.front(),
+            right: transform.right(),
+            up: transform.up(),
+            matrix: transform.get_matrix(),
+        }
+    }
+
+    // positions stay in double precision, only rotation and scale go through the f32 matrix
+    pub fn mul_transform(&self, local: &Transform) -> Self {
+        let basis: Matrix3d = self.matrix.fixed_view::<3, 3>(0, 0).map(|value| value as f64);
+        Self {
+            entity: local.entity,
+            one_frame: false,
+            position: self.position + basis * local.position(),
+            front: (basis * local.front()).normalize(),
+            right: (basis * local.right()).normalize(),
+            up: (basis * local.up()).normalize(),
+            matrix: self.matrix * local.get_matrix(),
+        }
+    }
+
+    pub fn position(&self) -> Vector3d {
+        self.position
+    }
+
+    pub fn front(&self) -> Vector3d {
+        self.front
+    }
+
+    pub fn right(&self) -> Vector3d {
+        self.right
+    }
+
+    pub fn up(&self) -> Vector3d {
+        self.up
+    }
+
+    pub fn get_matrix(&self) -> Matrix4x4 {
+        self.matrix
+    }
+
+    pub fn world_matrix(&self) -> Matrix4d {
+        let mut matrix: Matrix4d = self.matrix.map(|value| value as f64);
+        matrix.fixed_view_mut::<3, 1>(0, 3).copy_from(&self.position);
+        matrix
+    }
+
+    // compared before writing so untouched hierarchies don't show up in `Changed`
+    pub fn same_as(&self, other: &GlobalTransform) -> bool {
+        self.position == other.position
+            && self.matrix == other.matrix
+            && self.front == other.front
+            && self.right == other.right
+            && self.up == other.up
+    }
+}
+
+impl Default for GlobalTransform {
+    fn default() -> Self {
+        Self::from_transform(&Transform::default())
+    }
+}
@@ -4,13 +4,14 @@ use web_sys::WebGlVertexArrayObject;
 
 use webgl::WebGlRenderer;
 
-use ecs::Component;
+use ecs::{Component, Entity};
 
-use component::component;
+use component::{bundle, component};
 
+use crate::components::Transform;
 use crate::types::{Material, MeshData};
 
-#[component]
+#[component(clone)]
 pub struct Mesh {
     pub mesh_data: Rc<MeshData>,
     pub mesh_id: Rc<WebGlVertexArrayObject>,
@@ -18,12 +19,33 @@ pub struct Mesh {
 }
 
 impl Mesh {
+    pub fn new(
+        mesh_data: Rc<MeshData>,
+        mesh_id: Rc<WebGlVertexArrayObject>,
+        material: Option<Rc<Material>>,
+    ) -> Self {
+        Self {
+            entity: Entity::default(),
+            one_frame: false,
+            mesh_data,
+            mesh_id,
+            material,
+        }
+    }
+
     pub fn create(
         renderer: &WebGlRenderer,
-        entity: usize,
+        entity: Entity,
         mesh_data: MeshData,
         material: Option<Rc<Material>>,
     ) {
         let mesh_id = renderer.create_mesh(mesh_data.raw_vertices(), mesh_data.raw_indices(), webgl::DrawMode::Static);
     }
 }
+
+#[bundle]
+#[derive(Clone)]
+pub struct MeshBundle {
+    pub transform: Transform,
+    pub mesh: Mesh,
+}
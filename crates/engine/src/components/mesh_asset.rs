@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ecs::serde::{Deserialize, Serialize};
+use ecs::{Component, Entity};
+
+use component::component;
+use webgl::WebGlRenderer;
+use web_sys::{WebGlProgram, WebGlTexture, WebGlVertexArrayObject};
+
+use crate::components::Mesh;
+use crate::types::{Material, MeshData, Texture, Tlu};
+use crate::AssetMan;
+
+// asset paths a `Mesh` is built from, saved in place of the GPU handles
+#[component(clone, serde)]
+pub struct MeshAsset {
+    pub mesh: String,
+    pub material: Option<MaterialAsset>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(crate = "ecs::serde")]
+pub struct MaterialAsset {
+    pub shader: String,
+    pub shininess: f32,
+    pub diffuse: Option<String>,
+    pub specular: Option<String>,
+}
+
+impl MeshAsset {
+    pub fn new(mesh: &str, material: Option<MaterialAsset>) -> Self {
+        Self {
+            entity: Entity::default(),
+            one_frame: false,
+            mesh: mesh.to_string(),
+            material,
+        }
+    }
+
+    // `None` until every asset is loaded, nothing is created on the GPU before that
+    pub fn create_mesh(&self, cache: &mut MeshAssetCache, asset_man: &AssetMan, renderer: &WebGlRenderer) -> Option<Mesh> {
+        let mesh_data: Rc<MeshData> = asset_man.get_asset(self.mesh.clone())?;
+        let material = match &self.material {
+            Some(material) => Some(material.create_material(cache, asset_man, renderer)?),
+            None => None,
+        };
+
+        let mesh_id = cache
+            .meshes
+            .entry(self.mesh.clone())
+            .or_insert_with(|| {
+                renderer
+                    .create_mesh(
+                        mesh_data.raw_vertices(),
+                        mesh_data.raw_indices(),
+                        webgl::DrawMode::Static,
+                    )
+                    .vao()
+            })
+            .clone();
+
+        let mut mesh = Mesh::new(mesh_data, mesh_id, material);
+        mesh.entity = self.entity;
+        Some(mesh)
+    }
+}
+
+impl MaterialAsset {
+    // equal assets share one `Material`, so their meshes end up in the same batch
+    pub fn create_material(
+        &self,
+        cache: &mut MeshAssetCache,
+        asset_man: &AssetMan,
+        renderer: &WebGlRenderer,
+    ) -> Option<Rc<Material>> {
+        let key = format!("{}|{}|{:?}|{:?}", self.shader, self.shininess, self.diffuse, self.specular);
+        if let Some(material) = cache.materials.get(&key) {
+            return Some(Rc::clone(material));
+        }
+
+        let shader: Rc<Tlu> = asset_man.get_asset(self.shader.clone())?;
+        let texture = |path: &Option<String>| match path {
+            Some(path) => asset_man
+                .get_asset::<Texture>(path.clone())
+                .map(|texture| Some((path.clone(), texture))),
+            None => Some(None),
+        };
+        let diffuse = texture(&self.diffuse)?;
+        let specular = texture(&self.specular)?;
+
+        let program = cache
+            .programs
+            .entry(self.shader.clone())
+            .or_insert_with(|| renderer.create_program(shader.vert(), shader.frag()).program())
+            .clone();
+        let mut texture = |texture: Option<(String, Rc<Texture>)>| {
+            texture.map(|(path, texture)| {
+                cache
+                    .textures
+                    .entry(path)
+                    .or_insert_with(|| {
+                        let dimensions = texture.get_dimensions();
+                        renderer
+                            .create_texture(texture.get_data(), dimensions.0, dimensions.1)
+                            .texture()
+                    })
+                    .clone() as Rc<dyn AsRef<WebGlTexture>>
+            })
+        };
+        let diffuse = texture(diffuse);
+        let specular = texture(specular);
+
+        let material = Rc::new(Material::new(shader, program, self.shininess, diffuse, specular));
+        cache.materials.insert(key, Rc::clone(&material));
+        Some(material)
+    }
+}
+
+// GPU objects built for `MeshAsset`s, keyed by asset path
+#[derive(Default)]
+pub struct MeshAssetCache {
+    meshes: HashMap<String, Rc<WebGlVertexArrayObject>>,
+    programs: HashMap<String, Rc<WebGlProgram>>,
+    textures: HashMap<String, Rc<WebGlTexture>>,
+    materials: HashMap<String, Rc<Material>>,
+}
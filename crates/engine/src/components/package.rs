@@ -9,4 +9,6 @@ use crate::types::{DrawCallInfo, Material};
 #[component]
 pub struct DrawPackage {
     pub meshes: HashMap<Rc<Material>, Vec<DrawCallInfo>>,
+    // drawables carrying `RenderLayers` when batched, so a removed one forces a rebuild
+    pub layered: usize,
 }
\ No newline at end of file
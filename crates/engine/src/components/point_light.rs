@@ -3,7 +3,7 @@ use ecs::Component;
 use component::component;
 use math::Color;
 
-#[component]
+#[component(clone, serde)]
 pub struct PointLight {
     pub diffuse: Color,
     pub specular: Color,
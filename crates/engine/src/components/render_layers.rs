@@ -0,0 +1,21 @@
+use ecs::{Component, Entity};
+
+use component::component;
+
+// bit mask matched against `Camera::culling_mask`, meshes and skyboxes without it are on `RenderLayers::DEFAULT`
+#[component(clone, serde, rollback)]
+pub struct RenderLayers {
+    pub mask: u32,
+}
+
+impl RenderLayers {
+    pub const DEFAULT: u32 = 1;
+
+    pub fn new(mask: u32) -> Self {
+        Self {
+            entity: Entity::default(),
+            one_frame: false,
+            mask,
+        }
+    }
+}
@@ -2,7 +2,7 @@ use std::rc::Rc;
 
 use web_sys::{WebGlProgram, WebGlTexture, WebGlVertexArrayObject};
 
-use ecs::Component;
+use ecs::{Component, Entity};
 
 use component::component;
 
@@ -19,7 +19,7 @@ pub struct Skybox {
 
 impl Skybox {
     pub fn create(
-        entity: usize,
+        entity: Entity,
         shader: Rc<Tlu>,
         program: Rc<WebGlProgram>,
         mesh: WebGlVertexArrayObject,
@@ -1,31 +1,30 @@
-use ecs::Component;
+use ecs::{Component, Entity};
 
 use component::component;
 
-use math::{to_double_vector, Matrix4x4, Rotation3, Vector3d, Vector3f};
+use math::{to_double_vector, Matrix4d, Matrix4x4, UnitQuaternion, Vector3d, Vector3f};
 
-#[component]
+// local axes: front is -Z, right is +X, up is +Y
+#[component(clone, serde, rollback)]
 pub struct Transform {
+    #[reflect(setter = set_position)]
     position: Vector3d,
-    euler_angles: Vector3f,
+    #[reflect(setter = set_rotation)]
+    rotation: UnitQuaternion,
+    #[reflect(setter = set_scale)]
     scale: Vector3f,
-    front: Vector3d,
-    right: Vector3d,
-    up: Vector3d,
+    #[reflect(skip)]
     matrix: Matrix4x4,
 }
 
 impl Transform {
-    pub fn new(entity: usize) -> Self {
+    pub fn new(entity: Entity) -> Self {
         Self {
             entity: entity,
             one_frame: false,
             position: Vector3d::zeros(),
-            euler_angles: Vector3f::zeros(),
+            rotation: UnitQuaternion::identity(),
             scale: Vector3f::new(1.0, 1.0, 1.0),
-            front: Vector3d::new(0.0, 0.0, -1.0),
-            right: Vector3d::new(1.0, 0.0, 0.0),
-            up: Vector3d::new(0.0, 1.0, 0.0),
             matrix: Matrix4x4::identity(),
         }
     }
@@ -34,8 +33,14 @@ impl Transform {
         self.position
     }
 
+    pub fn rotation(&self) -> UnitQuaternion {
+        self.rotation
+    }
+
+    // degrees around x, y and z, applied in that order
     pub fn euler_angles(&self) -> Vector3f {
-        self.euler_angles
+        let (x, y, z) = self.rotation.euler_angles();
+        Vector3f::new(x.to_degrees(), y.to_degrees(), z.to_degrees())
     }
 
     pub fn scale(&self) -> Vector3f {
@@ -43,25 +48,32 @@ impl Transform {
     }
 
     pub fn front(&self) -> Vector3d {
-        self.front
+        to_double_vector(self.rotation * Vector3f::new(0.0, 0.0, -1.0))
     }
 
     pub fn right(&self) -> Vector3d {
-        self.right
+        to_double_vector(self.rotation * Vector3f::new(1.0, 0.0, 0.0))
     }
 
     pub fn up(&self) -> Vector3d {
-        self.up
+        to_double_vector(self.rotation * Vector3f::new(0.0, 1.0, 0.0))
     }
 
     pub fn set_position(&mut self, value: Vector3d) {
         self.position = value;
     }
 
-    pub fn set_euler_angles(&mut self, value: Vector3f) {
-        self.euler_angles = value;
+    pub fn set_rotation(&mut self, value: UnitQuaternion) {
+        self.rotation = value;
         self.update_transform();
-        self.update_vectors();
+    }
+
+    pub fn set_euler_angles(&mut self, value: Vector3f) {
+        self.set_rotation(UnitQuaternion::from_euler_angles(
+            value.x.to_radians(),
+            value.y.to_radians(),
+            value.z.to_radians(),
+        ));
     }
 
     pub fn set_scale(&mut self, value: Vector3f) {
@@ -69,34 +81,80 @@ impl Transform {
         self.update_transform();
     }
 
-    fn update_transform(&mut self) {
-        let rotation = self.euler_angles;
-        let scale = Matrix4x4::new_nonuniform_scaling(&self.scale);
-        self.matrix = Rotation3::from_euler_angles(rotation.x, rotation.y, rotation.z).to_homogeneous() * scale;
+    // turns around a world axis through the own position
+    pub fn rotate(&mut self, axis: Vector3f, degrees: f32) {
+        let rotation = UnitQuaternion::from_scaled_axis(axis.normalize() * degrees.to_radians());
+        self.set_rotation(rotation * self.rotation);
+    }
+
+    // orbits `point`, turning with it
+    pub fn rotate_around(&mut self, point: Vector3d, axis: Vector3f, degrees: f32) {
+        let rotation = UnitQuaternion::from_scaled_axis(axis.normalize() * degrees.to_radians());
+        self.position = point + rotation.cast::<f64>() * (self.position - point);
+        self.set_rotation(rotation * self.rotation);
+    }
+
+    // points the front at `target`, unchanged if the target is on the up axis
+    pub fn look_at(&mut self, target: Vector3d, up: Vector3d) {
+        let direction = math::to_single_vector(target - self.position);
+        let up = math::to_single_vector(up);
+        if direction.cross(&up).norm_squared() <= f32::EPSILON {
+            return;
+        }
+        self.set_rotation(UnitQuaternion::face_towards(&-direction, &up));
     }
 
-    fn update_vectors(&mut self) {
-        let world_up = Vector3f::new(0f32, 1f32, 0f32);
+    // moves the rotation a fraction `t` of the way to `target`
+    pub fn slerp(&mut self, target: &UnitQuaternion, t: f32) {
+        let rotation = self
+            .rotation
+            .try_slerp(target, t, f32::EPSILON)
+            .unwrap_or_else(|| self.rotation.nlerp(target, t));
+        self.set_rotation(rotation);
+    }
 
-        let angles = self.euler_angles;
-        let pitch = angles.x;
-        let yaw = angles.y;
+    // moves along the own axes, e.g. `Vector3d::new(0.0, 0.0, -1.0)` is one unit forward
+    pub fn translate_local(&mut self, offset: Vector3d) {
+        self.position += self.transform_direction(offset);
+    }
 
-        let front = Vector3f::new(
-            yaw.to_radians().cos() * pitch.to_radians().cos(),
-            pitch.to_radians().sin(),
-            yaw.to_radians().sin() * pitch.to_radians().cos()
-        ).normalize();
+    // from local space to the parent's space, the world for roots
+    pub fn transform_point(&self, point: Vector3d) -> Vector3d {
+        let scaled = point.component_mul(&to_double_vector(self.scale));
+        self.position + self.rotation.cast::<f64>() * scaled
+    }
 
-        let right = front.cross(&world_up).normalize();
-        let up = right.cross(&front).normalize();
+    // rotation only, the length is kept
+    pub fn transform_direction(&self, direction: Vector3d) -> Vector3d {
+        self.rotation.cast::<f64>() * direction
+    }
 
-        self.front = to_double_vector(front);
-        self.right = to_double_vector(right);
-        self.up = to_double_vector(up);
+    // an axis scaled to zero has no inverse, the point lands on 0 along it
+    pub fn inverse_transform_point(&self, point: Vector3d) -> Vector3d {
+        let local = self.rotation.cast::<f64>().inverse() * (point - self.position);
+        let scale = to_double_vector(self.scale);
+        local.zip_map(&scale, |value, scale| if scale == 0.0 { 0.0 } else { value / scale })
+    }
+
+    // relative to the parent like `transform_point`, `GlobalTransform::world_matrix` is the world-space one.
+    // `get_matrix` without the translation is what goes to the GPU, positions are split there
+    pub fn local_matrix_d(&self) -> Matrix4d {
+        let scale = Matrix4d::new_nonuniform_scaling(&to_double_vector(self.scale));
+        Matrix4d::new_translation(&self.position) * self.rotation.cast::<f64>().to_homogeneous() * scale
+    }
+
+    fn update_transform(&mut self) {
+        let scale = Matrix4x4::new_nonuniform_scaling(&self.scale);
+        self.matrix = self.rotation.to_homogeneous() * scale;
     }
 
     pub fn get_matrix(&self) -> Matrix4x4 {
         self.matrix
     }
 }
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::new(Entity::default())
+    }
+}
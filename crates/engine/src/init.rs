@@ -1,25 +1,108 @@
 #[macro_export]
 macro_rules! init_world {
-    ($($struct_name:ident),*) => {
+    ($($struct_name:ident),* $(,)? $(; tags: $($tag:ident),* $(,)?)?) => {
 
         use $crate::ecs::init_ecs;
         use $crate::app::App;
         use $crate::webgl::WebGlRenderer;
         use $crate::Services;
         use $crate::components::*;
-        use $crate::types::DrawCallInfo;
+        use $crate::types::{DrawCallInfo, SceneData};
         use $crate::components::DrawPackage;
         use $crate::AssetMan;
         use $crate::WebGl2RenderingContext;
 
         use $crate::batch_system;
+        use $crate::mesh_asset_system;
         use $crate::render_system;
+        use $crate::transform_propagate_system;
         use $crate::update_camera_system;
 
-        init_ecs! {Transform, Camera, DrawPackage, Mesh, DirectionalLight, PointLight, SpotLight, Skybox, SceneData,
+        init_ecs! {Transform, GlobalTransform, Parent, Children, Camera, RenderLayers, DrawPackage, Mesh, MeshAsset, DirectionalLight, PointLight, SpotLight, Skybox,
             $(
                 $struct_name
             ),*
+            ; tags: $($($tag),*)?
+        }
+
+        impl World {
+            // `false` if either entity is dead or the parent is the child itself or one of its descendants
+            pub fn set_parent(&mut self, child: $crate::ecs::Entity, parent: $crate::ecs::Entity) -> bool {
+                if !self.is_alive(child) || !self.is_alive(parent) {
+                    return false;
+                }
+                let mut ancestor = Some(parent);
+                while let Some(entity) = ancestor {
+                    if entity == child {
+                        return false;
+                    }
+                    ancestor = self.get_parent(entity).map(|parent| parent.parent);
+                }
+
+                // the `Parent` hooks move the child between the lists
+                $crate::ecs::Bundle::<World>::insert(
+                    Parent { entity: child, one_frame: false, parent },
+                    self,
+                    child,
+                );
+                true
+            }
+
+            pub fn remove_parent(&mut self, child: $crate::ecs::Entity) -> Option<$crate::ecs::Entity> {
+                self.remove_component::<Parent>(child).map(|parent| parent.parent)
+            }
+
+            // `destroy_entity` leaves the children in place as roots, this takes them along
+            pub fn despawn_recursive(&mut self, entity: $crate::ecs::Entity) {
+                self.remove_parent(entity);
+                let mut pending = vec![entity];
+                while let Some(entity) = pending.pop() {
+                    if let Some(children) = self.get_children(entity) {
+                        pending.extend(children.children.iter().copied());
+                    }
+                    self.destroy_entity(entity);
+                }
+            }
+        }
+
+        impl $crate::ecs::ComponentHooks<World> for Parent {
+            // joins the parent's list, however the `Parent` was inserted
+            fn on_add(world: &mut World, entity: $crate::ecs::Entity) {
+                let parent = world.get_parent(entity).unwrap().parent;
+                if !world.is_alive(parent) {
+                    return;
+                }
+                match world.get_children_mut(parent) {
+                    Some(children) if children.children.contains(&entity) => {}
+                    Some(children) => children.children.push(entity),
+                    None => $crate::ecs::Bundle::<World>::insert(
+                        Children { entity: parent, one_frame: false, children: vec![entity] },
+                        world,
+                        parent,
+                    ),
+                }
+            }
+
+            // leaves the parent's list, the transform is touched so propagation picks up the new root
+            fn on_remove(world: &mut World, entity: $crate::ecs::Entity) {
+                let parent = world.get_parent(entity).unwrap().parent;
+                if let Some(children) = world.get_children_mut(parent) {
+                    children.children.retain(|child| *child != entity);
+                }
+                world.get_transform_mut(entity);
+            }
+        }
+
+        impl $crate::ecs::ComponentHooks<World> for Children {
+            // children of a destroyed parent stay behind as roots
+            fn on_remove(world: &mut World, entity: $crate::ecs::Entity) {
+                let children = world.get_children(entity).unwrap().children.clone();
+                for child in children {
+                    if world.get_parent(child).is_some_and(|parent| parent.parent == entity) {
+                        world.remove_component::<Parent>(child);
+                    }
+                }
+            }
         }
 
         pub type InitSystem = fn(services: &mut Services, state: &mut AppState, world: &mut World);
@@ -28,39 +111,120 @@ macro_rules! init_world {
         pub struct Engine {
             world: World,
             init_systems: Vec<InitSystem>,
-            tick_systems: Vec<System>,
+            schedule: $crate::schedule::Schedule<World>,
             services: Services,
         }
 
         impl Engine {
             pub fn new() -> Self {
-                let mut world = World::create();
-                SceneData {
-                    entity: world.create_entity(),
-                    one_frame: false,
-                    ambient: Color::new(0.2, 0.2, 0.2),
-                    env: None,
-                }.add(&mut world);
-
-                Engine {
-                    world: world,
-                    init_systems: vec![
-                    ],
-                    tick_systems: vec![
-                        update_camera_system!{},
-                        batch_system!{},
-                        render_system!{},
-                    ],
+                let mut engine = Self::empty();
+                engine.add_plugin($crate::RenderPlugin);
+                engine
+            }
+
+            // only transform propagation, add a render plugin of your own
+            pub fn empty() -> Self {
+                let mut engine = Engine {
+                    world: World::create(),
+                    init_systems: vec![],
+                    schedule: $crate::schedule::Schedule::new(),
                     services: Services::new(),
-                }
+                };
+                engine.add_plugin($crate::TransformPlugin);
+                engine
+            }
+
+            pub fn add_plugin(&mut self, plugin: impl $crate::Plugin<Engine>) {
+                plugin.build(self);
             }
 
             pub fn register_init(&mut self, system: InitSystem) {
                 self.init_systems.push(system);
             }
 
+            // runs in the Update stage, use `add_system` to pick a stage or ordering
             pub fn register_tick(&mut self, system: System) {
-                self.tick_systems.push(system);
+                self.schedule.add_system(system);
+            }
+
+            pub fn add_system(&mut self, system: $crate::schedule::SystemConfig<World>) {
+                self.schedule.add_system(system);
+            }
+
+            // reports ordering cycles and unknown labels before the first frame
+            pub fn build(&mut self) -> Result<(), $crate::schedule::ScheduleError> {
+                self.schedule.build()
+            }
+        }
+
+        impl $crate::EngineBuilder for Engine {
+            type World = World;
+
+            fn register_init(&mut self, system: InitSystem) {
+                Engine::register_init(self, system);
+            }
+
+            fn register_tick(&mut self, system: System) {
+                Engine::register_tick(self, system);
+            }
+
+            fn add_system(&mut self, system: $crate::schedule::SystemConfig<World>) {
+                Engine::add_system(self, system);
+            }
+
+            fn insert_resource<T: 'static>(&mut self, value: T) {
+                self.world.insert_resource(value);
+            }
+
+            fn services(&mut self) -> &mut Services {
+                &mut self.services
+            }
+
+            fn world(&mut self) -> &mut World {
+                &mut self.world
+            }
+        }
+
+        impl $crate::Plugin<Engine> for $crate::TransformPlugin {
+            fn build(&self, engine: &mut Engine) {
+                engine.add_system(
+                    $crate::schedule::SystemConfig::new(transform_propagate_system!{})
+                        .in_stage($crate::schedule::Stage::PostUpdate)
+                        .label($crate::schedule::PROPAGATE_TRANSFORMS),
+                );
+            }
+        }
+
+        impl $crate::Plugin<Engine> for $crate::RenderPlugin {
+            fn build(&self, engine: &mut Engine) {
+                engine.world.insert_resource(SceneData {
+                    ambient: Color::new(0.2, 0.2, 0.2),
+                    env: None,
+                });
+                engine.world.insert_resource(MeshAssetCache::default());
+
+                engine.add_system(
+                    $crate::schedule::SystemConfig::new(mesh_asset_system!{})
+                        .in_stage($crate::schedule::Stage::PreUpdate)
+                        .label($crate::schedule::MESH_ASSETS),
+                );
+                engine.add_system(
+                    $crate::schedule::SystemConfig::new(update_camera_system!{})
+                        .in_stage($crate::schedule::Stage::PostUpdate)
+                        .label($crate::schedule::UPDATE_CAMERA)
+                        .after($crate::schedule::PROPAGATE_TRANSFORMS),
+                );
+                engine.add_system(
+                    $crate::schedule::SystemConfig::new(batch_system!{})
+                        .in_stage($crate::schedule::Stage::Render)
+                        .label($crate::schedule::BATCH),
+                );
+                engine.add_system(
+                    $crate::schedule::SystemConfig::new(render_system!{})
+                        .in_stage($crate::schedule::Stage::Render)
+                        .label($crate::schedule::RENDER)
+                        .after($crate::schedule::BATCH),
+                );
             }
         }
 
@@ -73,15 +237,16 @@ macro_rules! init_world {
             fn on_tick(&mut self, state: &mut AppState) {
                 for system in self.init_systems.iter() {
                     system(&mut self.services, state, &mut self.world);
+                    self.world.apply_commands();
                 }
 
                 self.init_systems.clear();
 
-                for system in self.tick_systems.iter() {
-                    system(&mut self.services, state, &mut self.world);
-                }
+                self.schedule.run(&mut self.services, state, &mut self.world, World::apply_commands);
 
                 self.world.clear_one_frame();
+                self.world.update_events();
+                self.world.clear_trackers();
             }
 
             fn on_resize(&mut self, width: i32, height: i32) {
@@ -1,11 +1,14 @@
 pub mod assetman;
 pub mod components;
 pub mod init;
+pub mod plugin;
+pub mod schedule;
 pub mod services;
 pub mod types;
 mod systems;
 
 pub use assetman::AssetMan;
+pub use plugin::{EngineBuilder, Plugin, RenderPlugin, TransformPlugin};
 pub use services::Services;
 
 pub use ecs;
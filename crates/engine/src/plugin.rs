@@ -0,0 +1,24 @@
+use crate::schedule::{SystemConfig, SystemFn};
+use crate::Services;
+
+// implemented by the `Engine` that `init_world!` generates, so plugin crates can stay generic over the world
+pub trait EngineBuilder {
+    type World;
+
+    fn register_init(&mut self, system: SystemFn<Self::World>);
+    fn register_tick(&mut self, system: SystemFn<Self::World>);
+    fn add_system(&mut self, system: SystemConfig<Self::World>);
+    fn insert_resource<T: 'static>(&mut self, value: T);
+    fn services(&mut self) -> &mut Services;
+    fn world(&mut self) -> &mut Self::World;
+}
+
+pub trait Plugin<E> {
+    fn build(&self, engine: &mut E);
+}
+
+// hierarchy propagation into `GlobalTransform`, added by `Engine::empty` and so by `Engine::new`
+pub struct TransformPlugin;
+
+// mesh assets, camera update, batching and rendering, added by `Engine::new`
+pub struct RenderPlugin;
@@ -0,0 +1,218 @@
+use webapp::app::AppState;
+
+use crate::Services;
+
+pub const MESH_ASSETS: &str = "mesh_assets";
+// first in `PostUpdate`, systems there that move transforms go `before` it
+pub const PROPAGATE_TRANSFORMS: &str = "propagate_transforms";
+pub const UPDATE_CAMERA: &str = "update_camera";
+pub const BATCH: &str = "batch";
+pub const RENDER: &str = "render";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    PreUpdate,
+    Update,
+    PostUpdate,
+    Render,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 4] = [Stage::PreUpdate, Stage::Update, Stage::PostUpdate, Stage::Render];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScheduleError {
+    // a `before` or `after` names a label no system carries
+    UnknownLabel(&'static str),
+    // a `before` or `after` names a label carried in a stage that runs the other way round
+    CrossStage(&'static str),
+    Cycle(Stage),
+}
+
+impl std::fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleError::UnknownLabel(label) => write!(f, "no system is labeled {:?}", label),
+            ScheduleError::CrossStage(label) => {
+                write!(f, "ordering against {:?} contradicts the order of the stages", label)
+            }
+            ScheduleError::Cycle(stage) => write!(f, "system ordering in stage {:?} has a cycle", stage),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+pub type SystemFn<W> = fn(services: &mut Services, state: &mut AppState, world: &mut W);
+pub type RunCondition<W> = fn(state: &AppState, world: &W) -> bool;
+
+pub struct SystemConfig<W> {
+    system: SystemFn<W>,
+    stage: Stage,
+    labels: Vec<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    conditions: Vec<RunCondition<W>>,
+    last_run: u32,
+}
+
+impl<W> SystemConfig<W> {
+    pub fn new(system: SystemFn<W>) -> Self {
+        Self {
+            system,
+            stage: Stage::Update,
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+            conditions: Vec::new(),
+            last_run: 0,
+        }
+    }
+
+    pub fn in_stage(mut self, stage: Stage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.labels.push(label);
+        self
+    }
+
+    // ordering only applies to systems of the same stage
+    pub fn before(mut self, label: &'static str) -> Self {
+        self.before.push(label);
+        self
+    }
+
+    pub fn after(mut self, label: &'static str) -> Self {
+        self.after.push(label);
+        self
+    }
+
+    // the system is skipped for the frame unless every condition holds
+    pub fn run_if(mut self, condition: RunCondition<W>) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    fn runs_after(&self, other: &SystemConfig<W>) -> bool {
+        self.after.iter().any(|label| other.labels.contains(label))
+            || other.before.iter().any(|label| self.labels.contains(label))
+    }
+}
+
+impl<W> From<SystemFn<W>> for SystemConfig<W> {
+    fn from(system: SystemFn<W>) -> Self {
+        Self::new(system)
+    }
+}
+
+pub struct Schedule<W> {
+    systems: Vec<SystemConfig<W>>,
+    order: Vec<usize>,
+    dirty: bool,
+}
+
+impl<W> Schedule<W> {
+    pub fn new() -> Self {
+        Self {
+            systems: Vec::new(),
+            order: Vec::new(),
+            dirty: false,
+        }
+    }
+
+    pub fn add_system(&mut self, system: impl Into<SystemConfig<W>>) {
+        self.systems.push(system.into());
+        self.dirty = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    // checks the ordering once systems are added, `run` does it too but panics on errors
+    pub fn build(&mut self) -> Result<(), ScheduleError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        for system in self.systems.iter() {
+            let before = system.before.iter().map(|label| (label, true));
+            let after = system.after.iter().map(|label| (label, false));
+            for (label, before) in before.chain(after) {
+                let mut stages = self
+                    .systems
+                    .iter()
+                    .filter(|other| other.labels.contains(label))
+                    .map(|other| other.stage)
+                    .peekable();
+                if stages.peek().is_none() {
+                    return Err(ScheduleError::UnknownLabel(label));
+                }
+                // ordering across stages is already decided by the stages themselves
+                if stages.any(|stage| if before { stage < system.stage } else { stage > system.stage }) {
+                    return Err(ScheduleError::CrossStage(label));
+                }
+            }
+        }
+
+        self.order = self.sort()?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    // stages in order, inside a stage registration order unless a label says otherwise
+    fn sort(&self) -> Result<Vec<usize>, ScheduleError> {
+        let mut order = Vec::with_capacity(self.systems.len());
+        for stage in Stage::ALL {
+            let mut pending: Vec<usize> = (0..self.systems.len())
+                .filter(|i| self.systems[*i].stage == stage)
+                .collect();
+
+            while !pending.is_empty() {
+                let ready = pending.iter().position(|i| {
+                    !pending
+                        .iter()
+                        .any(|j| i != j && self.systems[*i].runs_after(&self.systems[*j]))
+                });
+                match ready {
+                    Some(position) => order.push(pending.remove(position)),
+                    None => return Err(ScheduleError::Cycle(stage)),
+                }
+            }
+        }
+        Ok(order)
+    }
+}
+
+impl<W: ecs::SystemTicks> Schedule<W> {
+    // `sync` runs after every system that ran, e.g. to apply queued commands
+    pub fn run(&mut self, services: &mut Services, state: &mut AppState, world: &mut W, sync: fn(&mut W)) {
+        if let Err(error) = self.build() {
+            panic!("{}", error);
+        }
+
+        for index in self.order.iter() {
+            let system = &mut self.systems[*index];
+            if system.conditions.iter().all(|condition| condition(state, world)) {
+                world.begin_system(system.last_run);
+                (system.system)(services, state, world);
+                system.last_run = world.end_system();
+                sync(world);
+            }
+        }
+    }
+}
+
+impl<W> Default for Schedule<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
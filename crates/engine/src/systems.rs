@@ -1,3 +1,5 @@
 pub mod batch_system;
+pub mod mesh_asset_system;
 pub mod render_system;
+pub mod transform_propagate_system;
 pub mod update_camera_system;
@@ -2,17 +2,42 @@
 macro_rules! batch_system {
     () => {
         |services: &mut Services, state: &mut AppState, world: &mut World| {
-            let entity_batch_list = world.create_entity();
+            let batched: Option<(usize, usize)> = world
+                .query_ref::<&DrawPackage>()
+                .next()
+                .map(|package| (package.meshes.values().map(Vec::len).sum(), package.layered));
+            let drawable = world
+                .query_ref::<(&Mesh, &GlobalTransform)>()
+                .filter(|(mesh, _)| mesh.material.is_some())
+                .count();
+            let layered = world
+                .query_ref::<(&Mesh, &GlobalTransform, &RenderLayers)>()
+                .filter(|(mesh, _, _)| mesh.material.is_some())
+                .count();
+            let changed = world.query_ref::<($crate::ecs::Changed<Mesh>, &GlobalTransform)>().next().is_some()
+                || world.query_ref::<(&Mesh, $crate::ecs::Changed<GlobalTransform>)>().next().is_some()
+                || world.query_ref::<(&Mesh, $crate::ecs::Changed<RenderLayers>)>().next().is_some();
+
+            // removed meshes and layers change the counts, anything else shows up in the ticks
+            if !changed && batched == Some((drawable, layered)) {
+                return;
+            }
+
+            let commands = world.commands();
+
+            // last frame's package is replaced instead of piling up entities
+            for (entity, _) in world.query_ref::<($crate::ecs::Entity, &DrawPackage)>() {
+                commands.despawn(entity);
+            }
+
             let mut batch_list = DrawPackage {
-                entity: entity_batch_list,
-                one_frame: true,
+                entity: commands.spawn(),
+                one_frame: false,
                 meshes: HashMap::new(),
+                layered,
             };
 
-            for e in world.query().mesh().transform().fetch() {
-                let mesh = world.get_mesh(e).unwrap();
-                let transform = world.get_transform(e).unwrap();
-
+            for (mesh, transform, layers) in world.query_ref::<(&Mesh, &GlobalTransform, Option<&RenderLayers>)>() {
                 if let Some(mat) = &mesh.material {
                     if !batch_list.meshes.contains_key(mat) {
                         batch_list.meshes.insert(Rc::clone(mat), Vec::new());
@@ -27,12 +52,13 @@ macro_rules! batch_system {
                             index_count: mesh.mesh_data.raw_indices().len() as i32,
                             position1,
                             position2,
+                            layers: layers.map_or(RenderLayers::DEFAULT, |layers| layers.mask),
                         });
                     }
                 }
             }
 
-            batch_list.add(world);
+            commands.insert(batch_list);
         }
     };
 }
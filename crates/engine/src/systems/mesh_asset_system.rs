@@ -0,0 +1,29 @@
+#[macro_export]
+macro_rules! mesh_asset_system {
+    () => {
+        |services: &mut Services, state: &mut AppState, world: &mut World| {
+            let (asset_man, renderer) = match (services.resolve::<AssetMan>(), services.resolve::<WebGlRenderer>()) {
+                (Some(asset_man), Some(renderer)) => (asset_man, renderer),
+                _ => return,
+            };
+
+            // loaded scenes only carry asset paths, the GPU side is rebuilt here
+            let pending: Vec<MeshAsset> = world
+                .query_ref::<(&MeshAsset, Option<&Mesh>)>()
+                .filter(|(_, mesh)| mesh.is_none())
+                .map(|(asset, _)| asset.clone())
+                .collect();
+            if pending.is_empty() {
+                return;
+            }
+
+            let commands = world.commands();
+            let cache = world.resource_mut::<MeshAssetCache>().expect("RenderPlugin inserts a MeshAssetCache");
+            for asset in pending.iter() {
+                if let Some(mesh) = asset.create_mesh(cache, asset_man, renderer) {
+                    commands.insert(mesh);
+                }
+            }
+        }
+    };
+}
@@ -3,36 +3,38 @@ macro_rules! render_system {
     () => {
         |services: &mut Services, state: &mut AppState, world: &mut World| {
             let renderer = services.resolve::<WebGlRenderer>().unwrap();
-            renderer.clear(0.152, 0.214, 0.3, 1.0);
-            
-            let mut scene_data = None;
-            for e in world.query().scenedata().fetch() {
-                scene_data = world.get_scenedata(e);
-                break;
-            }
-
-            if scene_data.is_none() {
-                return;
-            }
-
-            let scene_data = scene_data.unwrap();
-
-            for e in world.query().camera().transform().fetch() {
-                let camera = world.get_camera(e).unwrap();
-                let camera_transform = world.get_transform(e).unwrap();
+            // whatever no camera viewport covers stays black
+            renderer.clear(0.0, 0.0, 0.0, 1.0);
+
+            let scene_data = match world.resource::<SceneData>() {
+                Some(scene_data) => scene_data,
+                None => return,
+            };
+
+            let mut cameras: Vec<_> = world.query_ref::<(&Camera, &GlobalTransform)>().collect();
+            cameras.sort_by_key(|(camera, _)| camera.order());
+
+            for (camera, camera_transform) in cameras {
+                let target = camera.target();
+                renderer.bind_render_target(target.map(|target| target.as_ref()));
+                let size = target.map_or(state.resolution(), |target| (target.width(), target.height()));
+                let (x, y, w, h) = camera.viewport().pixels(size);
+                renderer.set_viewport_rect(x, y, w, h);
+                renderer.set_scissor(Some((x, y, w, h)));
+                match camera.clear_mode() {
+                    ClearMode::ColorAndDepth => {
+                        let color = camera.clear_color();
+                        renderer.clear(color.x, color.y, color.z, 1.0);
+                    }
+                    ClearMode::DepthOnly => renderer.clear_depth(),
+                    ClearMode::Nothing => {}
+                }
+                renderer.set_scissor(None);
 
-                let (w, h) = state.resolution();
-                let projection = math::Matrix4x4::new_perspective(
-                    w as f32 / h as f32,
-                    camera.fov().to_radians(),
-                    camera.near(),
-                    camera.far(),
-                );
+                let projection = camera.projection_matrix(w as f32 / h as f32);
                 let view = camera.get_view_matrix();
 
-                for e1 in world.query().drawpackage().fetch() {
-                    let batch_list = world.get_drawpackage(e1).unwrap();
-
+                for batch_list in world.query_ref::<&DrawPackage>() {
                     for (material, frames) in batch_list.meshes.iter() {
                         renderer.update_state(&material.tlu.params());
 
@@ -72,10 +74,7 @@ macro_rules! render_system {
                             scene_data.ambient.as_slice(),
                         );
 
-                        for e in world.query().directionallight().transform().fetch() {
-                            let light = world.get_directionallight(e).unwrap();
-                            let transform = world.get_transform(e).unwrap();
-
+                        for (light, transform) in world.query_ref::<(&DirectionalLight, &GlobalTransform)>() {
                             renderer.set_uniform_vector3(
                                 program,
                                 "dirLight.direction",
@@ -94,10 +93,7 @@ macro_rules! render_system {
                         }
 
                         let mut point_light_index = 0;
-                        for e in world.query().pointlight().transform().fetch() {
-                            let light = world.get_pointlight(e).unwrap();
-                            let transform = world.get_transform(e).unwrap();
-
+                        for (light, transform) in world.query_ref::<(&PointLight, &GlobalTransform)>() {
                             let (pos_lo, pos_hi) = math::split_double(transform.position());
 
                             renderer.set_uniform_vector3(
@@ -132,10 +128,7 @@ macro_rules! render_system {
                         renderer.set_uniform_int(program, "point_light_count", point_light_index);
 
                         let mut spotlight_index = 0;
-                        for e in world.query().spotlight().transform().fetch() {
-                            let light = world.get_spotlight(e).unwrap();
-                            let transform = world.get_transform(e).unwrap();
-
+                        for (light, transform) in world.query_ref::<(&SpotLight, &GlobalTransform)>() {
                             let (pos_lo, pos_hi) = math::split_double(transform.position());
 
                             renderer.set_uniform_vector3(
@@ -179,7 +172,7 @@ macro_rules! render_system {
 
                         renderer.set_uniform_int(program, "spot_light_count", spotlight_index);
 
-                        for frame in frames {
+                        for frame in frames.iter().filter(|frame| camera.sees(frame.layers)) {
                             renderer.set_uniform_vector3(program, "model_pos_lo", frame.position1.as_slice());
                             renderer.set_uniform_vector3(program, "model_pos_hi", frame.position2.as_slice());
 
@@ -195,9 +188,10 @@ macro_rules! render_system {
                     }
                 }
 
-                for e1 in world.query().skybox().fetch() {
-                    let skybox = world.get_skybox(e1).unwrap();
-
+                let skyboxes = world
+                    .query_ref::<(&Skybox, Option<&RenderLayers>)>()
+                    .filter(|(_, layers)| camera.sees(layers.map_or(RenderLayers::DEFAULT, |layers| layers.mask)));
+                for (skybox, _) in skyboxes {
                     renderer.update_state(&skybox.shader.params());
 
                     renderer.use_program(Some(&skybox.program));
@@ -215,7 +209,15 @@ macro_rules! render_system {
                     renderer.use_mesh(Some(&skybox.mesh));
                     renderer.draw_arrays(skybox.vertex_count);
                 }
+
+                if let Some(target) = target {
+                    renderer.resolve_render_target(target);
+                    renderer.bind_render_target(None);
+                }
             }
+
+            let (w, h) = state.resolution();
+            renderer.set_viewport(w, h);
         }
     };
 }
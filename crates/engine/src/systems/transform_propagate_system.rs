@@ -0,0 +1,65 @@
+#[macro_export]
+macro_rules! transform_propagate_system {
+    () => {
+        |services: &mut Services, state: &mut AppState, world: &mut World| {
+            // moved or re-parented since the last run, removing a `Parent` touches the transform
+            let changed: std::collections::HashSet<$crate::ecs::Entity> = world
+                .query_ref::<($crate::ecs::Entity, $crate::ecs::Changed<Transform>)>()
+                .map(|(entity, _)| entity)
+                .chain(
+                    world
+                        .query_ref::<($crate::ecs::Entity, $crate::ecs::Changed<Parent>)>()
+                        .map(|(entity, _)| entity),
+                )
+                .collect();
+
+            // roots have no parent, or a parent without a transform
+            let mut pending: Vec<($crate::ecs::Entity, Option<GlobalTransform>)> = world
+                .query_ref::<($crate::ecs::Entity, &Transform, Option<&Parent>)>()
+                .filter(|(_, _, parent)| parent.map_or(true, |parent| world.get_transform(parent.parent).is_none()))
+                .map(|(entity, _, _)| (entity, None))
+                .collect();
+
+            // a recomputed entity hands its result down, the others keep what they have
+            while let Some((entity, parent_global)) = pending.pop() {
+                let transform = match world.get_transform(entity) {
+                    Some(transform) => transform,
+                    None => continue,
+                };
+                let global = match parent_global {
+                    Some(parent_global) => Some(parent_global.mul_transform(transform)),
+                    None if changed.contains(&entity) || world.get_globaltransform(entity).is_none() => {
+                        let parent_global = world
+                            .get_parent(entity)
+                            .filter(|parent| world.get_transform(parent.parent).is_some())
+                            .and_then(|parent| world.get_globaltransform(parent.parent));
+                        match parent_global {
+                            Some(parent_global) => Some(parent_global.mul_transform(transform)),
+                            None => Some(GlobalTransform::from_transform(transform)),
+                        }
+                    }
+                    None => None,
+                };
+
+                if let Some(children) = world.get_children(entity) {
+                    for child in children.children.iter() {
+                        if world.get_parent(*child).is_some_and(|parent| parent.parent == entity) {
+                            pending.push((*child, global.clone()));
+                        }
+                    }
+                }
+
+                let mut global = match global {
+                    Some(global) => global,
+                    None => continue,
+                };
+                global.entity = entity;
+                match world.get_globaltransform(entity) {
+                    Some(current) if current.same_as(&global) => {}
+                    Some(_) => *world.get_globaltransform_mut(entity).unwrap() = global,
+                    None => $crate::ecs::Bundle::<World>::insert(global, world, entity),
+                }
+            }
+        }
+    };
+}
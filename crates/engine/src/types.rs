@@ -3,9 +3,11 @@ mod material;
 mod texture;
 mod tlu;
 mod meshdata;
+mod scenedata;
 
 pub use drawcall::DrawCallInfo;
 pub use material::Material;
 pub use meshdata::MeshData;
+pub use scenedata::SceneData;
 pub use texture::Texture;
 pub use tlu::Tlu;
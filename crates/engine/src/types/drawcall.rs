@@ -9,4 +9,5 @@ pub struct DrawCallInfo {
     pub position1: Vector3f,
     pub position2: Vector3f,
     pub index_count: i32,
+    pub layers: u32,
 }
\ No newline at end of file
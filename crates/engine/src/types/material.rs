@@ -9,15 +9,16 @@ pub struct Material {
     pub tlu: Rc<Tlu>,
     program: Rc<WebGlProgram>,
     pub shininess: f32,
-    tex_duffuse: Option<Rc<WebGlTexture>>,
-    tex_specular: Option<Rc<WebGlTexture>>,
+    tex_duffuse: Option<Rc<dyn AsRef<WebGlTexture>>>,
+    tex_specular: Option<Rc<dyn AsRef<WebGlTexture>>>,
 }
 
 static MAT_ID_COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 impl Material {
-    pub fn new(tlu: Rc<Tlu>, program: Rc<WebGlProgram>, shininess: f32, tex_diffuse: Option<Rc<WebGlTexture>>,
-        tex_specular: Option<Rc<WebGlTexture>>) -> Self {
+    // textures are plain `WebGlTexture`s or render target textures, both behind an `Rc`
+    pub fn new(tlu: Rc<Tlu>, program: Rc<WebGlProgram>, shininess: f32, tex_diffuse: Option<Rc<dyn AsRef<WebGlTexture>>>,
+        tex_specular: Option<Rc<dyn AsRef<WebGlTexture>>>) -> Self {
         Material {
             id: MAT_ID_COUNTER.fetch_add(1, std::sync::atomic::Ordering::SeqCst),
             tlu,
@@ -42,14 +43,14 @@ impl Material {
 
     pub fn tex_diffuse(&self) -> Option<&WebGlTexture> {
         if let Some(tex) = &self.tex_duffuse {
-            return Some(tex);
+            return Some((**tex).as_ref());
         }
         None
     }
 
     pub fn tex_specular(&self) -> Option<&WebGlTexture> {
         if let Some(tex) = &self.tex_specular {
-            return Some(tex);
+            return Some((**tex).as_ref());
         }
         None
     }
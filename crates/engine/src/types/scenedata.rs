@@ -1,12 +1,8 @@
 use std::rc::Rc;
 
-use ecs::Component;
-
-use component::component;
 use math::Color;
 use web_sys::WebGlTexture;
 
-#[component]
 pub struct SceneData {
     pub ambient: Color,
     pub env: Option<Rc<WebGlTexture>>,
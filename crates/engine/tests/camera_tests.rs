@@ -0,0 +1,53 @@
+use ecs::Entity;
+use engine::components::{Camera, ClearMode, Projection, Viewport};
+use math::{Matrix4x4, Point3};
+
+#[test]
+fn test_viewport_pixels() {
+    let right_half = Viewport { x: 0.5, y: 0.0, width: 0.5, height: 1.0 };
+    assert_eq!((400, 0, 400, 600), right_half.pixels((800, 600)));
+    assert_eq!((0, 0, 800, 600), Viewport::FULL.pixels((800, 600)));
+
+    // never collapses to nothing
+    let tiny = Viewport { x: 0.0, y: 0.0, width: 0.0001, height: 0.0001 };
+    assert_eq!((0, 0, 1, 1), tiny.pixels((800, 600)));
+}
+
+#[test]
+fn test_perspective() {
+    let camera = Camera::new(Entity::default());
+    assert_eq!(Projection::Perspective { fov: 45.0 }, camera.projection());
+    assert_eq!(Some(45.0), camera.fov());
+    assert_eq!(
+        Matrix4x4::new_perspective(2.0, 45.0f32.to_radians(), camera.near(), camera.far()),
+        camera.projection_matrix(2.0)
+    );
+}
+
+#[test]
+fn test_orthographic() {
+    let camera = Camera::orthographic(Entity::default(), 5.0);
+    assert_eq!(None, camera.fov());
+
+    // the top right corner on the near plane ends up in the corner of clip space
+    let corner = camera.projection_matrix(2.0).transform_point(&Point3::new(10.0, 5.0, -camera.near()));
+    assert!((corner - Point3::new(1.0, 1.0, -1.0)).norm() < 1e-4);
+}
+
+#[test]
+fn test_culling_mask() {
+    let mut camera = Camera::default();
+    assert!(camera.sees(1 << 4));
+    camera.set_culling_mask(0b01);
+    assert!(camera.sees(0b11));
+    assert!(!camera.sees(0b10));
+}
+
+#[test]
+fn test_defaults() {
+    let camera = Camera::default();
+    assert_eq!(Viewport::FULL, camera.viewport());
+    assert_eq!(ClearMode::ColorAndDepth, camera.clear_mode());
+    assert_eq!(0, camera.order());
+    assert!(camera.target().is_none());
+}
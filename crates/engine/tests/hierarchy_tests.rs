@@ -0,0 +1,159 @@
+use std::rc::Rc;
+
+use ecs::{Component, Entity};
+use engine::init_world;
+use engine::schedule::{self, Stage, SystemConfig};
+use math::{Color, Vector3d, Vector3f};
+use webapp::app::AppState;
+
+init_world! {}
+
+fn spawn_at(engine: &mut Engine, position: Vector3d) -> Entity {
+    let mut transform = Transform::default();
+    transform.set_position(position);
+    engine.world.spawn().with(transform).id()
+}
+
+fn global_position(engine: &Engine, entity: Entity) -> Vector3d {
+    engine.world.get_globaltransform(entity).unwrap().position()
+}
+
+fn assert_near(expected: Vector3d, actual: Vector3d) {
+    assert!((expected - actual).norm() < 1e-4, "expected {:?}, got {:?}", expected, actual);
+}
+
+#[test]
+fn test_propagation() {
+    let mut engine = Engine::empty();
+    let parent = spawn_at(&mut engine, Vector3d::new(10.0, 0.0, 0.0));
+    let child = spawn_at(&mut engine, Vector3d::new(1.0, 0.0, 0.0));
+    engine.world.get_transform_mut(parent).unwrap().set_euler_angles(Vector3f::new(0.0, 90.0, 0.0));
+    assert!(engine.world.set_parent(child, parent));
+    assert!(!engine.world.set_parent(parent, child));
+
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(10.0, 0.0, -1.0), global_position(&engine, child));
+
+    // untouched hierarchies are left alone
+    let ticks = engine.world.ticks::<GlobalTransform>(child);
+    engine.on_tick(&mut AppState::new());
+    assert_eq!(ticks, engine.world.ticks::<GlobalTransform>(child));
+
+    engine.world.get_transform_mut(parent).unwrap().set_position(Vector3d::new(0.0, 5.0, 0.0));
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(0.0, 5.0, -1.0), global_position(&engine, child));
+}
+
+#[test]
+fn test_inserted_parent() {
+    let mut engine = Engine::empty();
+    let parent = spawn_at(&mut engine, Vector3d::new(10.0, 0.0, 0.0));
+    let mut transform = Transform::default();
+    transform.set_position(Vector3d::new(1.0, 0.0, 0.0));
+    let child = engine
+        .world
+        .spawn()
+        .with(transform)
+        .with(Parent { entity: Entity::default(), one_frame: false, parent })
+        .id();
+    assert_eq!(vec![child], engine.world.get_children(parent).unwrap().children);
+
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(11.0, 0.0, 0.0), global_position(&engine, child));
+
+    // children are rebuilt from the saved parents
+    let saved = engine.world.save_json().unwrap();
+    let loaded = &mut World::create();
+    let map = loaded.load_json(&saved).unwrap();
+    assert_eq!(vec![map.map(child)], loaded.get_children(map.map(parent)).unwrap().children);
+}
+
+#[test]
+fn test_dead_parent_or_child() {
+    let mut engine = Engine::empty();
+    let parent = spawn_at(&mut engine, Vector3d::zeros());
+    let child = spawn_at(&mut engine, Vector3d::zeros());
+    let dead = spawn_at(&mut engine, Vector3d::zeros());
+    engine.world.destroy_entity(dead);
+
+    assert!(!engine.world.set_parent(dead, parent));
+    assert!(engine.world.get_children(parent).is_none());
+    assert!(!engine.world.set_parent(child, dead));
+    assert!(engine.world.get_parent(child).is_none());
+}
+
+fn step(_: &mut Services, _: &mut AppState, world: &mut World) {
+    for transform in world.query_mut::<&mut Transform>() {
+        let position = transform.position();
+        transform.set_position(position + Vector3d::x());
+    }
+}
+
+#[test]
+fn test_moved_before_propagation() {
+    let mut engine = Engine::empty();
+    let entity = spawn_at(&mut engine, Vector3d::zeros());
+    engine.add_system(
+        SystemConfig::new(step)
+            .in_stage(Stage::PostUpdate)
+            .before(schedule::PROPAGATE_TRANSFORMS),
+    );
+    assert_eq!(Ok(()), engine.build());
+
+    // the move shows up in the same frame
+    for x in 1..3 {
+        engine.on_tick(&mut AppState::new());
+        assert_near(Vector3d::new(x as f64, 0.0, 0.0), global_position(&engine, entity));
+    }
+}
+
+#[test]
+fn test_reparenting() {
+    let mut engine = Engine::empty();
+    let first = spawn_at(&mut engine, Vector3d::new(10.0, 0.0, 0.0));
+    let second = spawn_at(&mut engine, Vector3d::new(0.0, 10.0, 0.0));
+    let child = spawn_at(&mut engine, Vector3d::new(1.0, 0.0, 0.0));
+    engine.world.set_parent(child, first);
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(11.0, 0.0, 0.0), global_position(&engine, child));
+
+    engine.world.set_parent(child, second);
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(1.0, 10.0, 0.0), global_position(&engine, child));
+    assert!(engine.world.get_children(first).unwrap().children.is_empty());
+    assert_eq!(vec![child], engine.world.get_children(second).unwrap().children);
+
+    assert_eq!(Some(second), engine.world.remove_parent(child));
+    engine.on_tick(&mut AppState::new());
+    assert_near(Vector3d::new(1.0, 0.0, 0.0), global_position(&engine, child));
+    assert!(engine.world.get_children(second).unwrap().children.is_empty());
+
+    // clones start as roots and don't show up in the parent's list
+    engine.world.set_parent(child, first);
+    let clone = engine.world.clone_entity(child).unwrap();
+    assert!(engine.world.get_parent(clone).is_none());
+    assert_eq!(vec![child], engine.world.get_children(first).unwrap().children);
+}
+
+#[test]
+fn test_despawn() {
+    let mut engine = Engine::empty();
+    let root = spawn_at(&mut engine, Vector3d::zeros());
+    let child = spawn_at(&mut engine, Vector3d::zeros());
+    let grandchild = spawn_at(&mut engine, Vector3d::zeros());
+    let other = spawn_at(&mut engine, Vector3d::zeros());
+    engine.world.set_parent(child, root);
+    engine.world.set_parent(grandchild, child);
+    engine.world.set_parent(other, root);
+
+    // a destroyed child leaves its parent's list, its own children become roots
+    engine.world.destroy_entity(child);
+    assert_eq!(vec![other], engine.world.get_children(root).unwrap().children);
+    assert!(engine.world.get_parent(grandchild).is_none());
+    engine.world.set_parent(grandchild, other);
+
+    engine.world.despawn_recursive(root);
+    assert!(!engine.world.is_alive(root));
+    assert!(!engine.world.is_alive(other));
+    assert!(!engine.world.is_alive(grandchild));
+}
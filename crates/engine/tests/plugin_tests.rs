@@ -0,0 +1,66 @@
+use std::rc::Rc;
+
+use ecs::Component;
+use engine::schedule::{Stage, SystemConfig};
+use engine::{init_world, Plugin};
+use math::Color;
+use webapp::app::AppState;
+
+init_world! {}
+
+type Log = Vec<&'static str>;
+
+fn first(_: &mut Services, _: &mut AppState, world: &mut World) {
+    world.resource_mut::<Log>().unwrap().push("first");
+}
+
+fn second(_: &mut Services, _: &mut AppState, world: &mut World) {
+    world.resource_mut::<Log>().unwrap().push("second");
+}
+
+fn early(_: &mut Services, _: &mut AppState, world: &mut World) {
+    world.resource_mut::<Log>().unwrap().push("early");
+}
+
+struct FirstPlugin;
+
+impl Plugin<Engine> for FirstPlugin {
+    fn build(&self, engine: &mut Engine) {
+        engine.world.insert_resource(Log::new());
+        engine.register_tick(first);
+    }
+}
+
+// relies on `FirstPlugin` having inserted the log
+struct SecondPlugin;
+
+impl Plugin<Engine> for SecondPlugin {
+    fn build(&self, engine: &mut Engine) {
+        engine.world.resource_mut::<Log>().unwrap().push("built");
+        engine.register_tick(second);
+        engine.add_system(SystemConfig::new(early).in_stage(Stage::PreUpdate));
+    }
+}
+
+#[test]
+fn test_plugin_order() {
+    let mut engine = Engine::empty();
+    engine.add_plugin(FirstPlugin);
+    engine.add_plugin(SecondPlugin);
+    assert_eq!(Ok(()), engine.build());
+
+    engine.on_tick(&mut AppState::new());
+    assert_eq!(&vec!["built", "early", "first", "second"], engine.world.resource::<Log>().unwrap());
+    assert_eq!(4, engine.schedule.len());
+}
+
+#[test]
+fn test_render_plugin() {
+    // propagation comes with every engine
+    assert_eq!(1, Engine::empty().schedule.len());
+
+    let mut engine = Engine::new();
+    assert_eq!(5, engine.schedule.len());
+    assert_eq!(Ok(()), engine.build());
+    assert!(engine.world.resource::<SceneData>().is_some());
+}
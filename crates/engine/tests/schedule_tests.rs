@@ -0,0 +1,142 @@
+use ecs::SystemTicks;
+use engine::schedule::{Schedule, ScheduleError, Stage, SystemConfig};
+use engine::Services;
+use webapp::app::AppState;
+
+// records what ran and the change window every system was given
+#[derive(Default)]
+struct Log {
+    entries: Vec<&'static str>,
+    windows: Vec<u32>,
+    tick: u32,
+}
+
+impl SystemTicks for Log {
+    fn begin_system(&mut self, last_run: u32) {
+        self.windows.push(last_run);
+    }
+
+    fn end_system(&mut self) -> u32 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+fn a(_: &mut Services, _: &mut AppState, log: &mut Log) {
+    log.entries.push("a");
+}
+
+fn b(_: &mut Services, _: &mut AppState, log: &mut Log) {
+    log.entries.push("b");
+}
+
+fn c(_: &mut Services, _: &mut AppState, log: &mut Log) {
+    log.entries.push("c");
+}
+
+fn sync(log: &mut Log) {
+    log.entries.push("sync");
+}
+
+fn never(_: &AppState, _: &Log) -> bool {
+    false
+}
+
+fn run(schedule: &mut Schedule<Log>) -> Vec<&'static str> {
+    let mut log = Log::default();
+    schedule.run(&mut Services::new(), &mut AppState::new(), &mut log, |_| {});
+    log.entries
+}
+
+#[test]
+fn test_stage_order() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).in_stage(Stage::Render));
+    schedule.add_system(SystemConfig::new(b).in_stage(Stage::PreUpdate));
+    schedule.add_system(c as fn(&mut Services, &mut AppState, &mut Log));
+
+    assert_eq!(vec!["b", "c", "a"], run(&mut schedule));
+}
+
+#[test]
+fn test_before_after() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("a").after("c"));
+    schedule.add_system(SystemConfig::new(b).label("b").before("c"));
+    schedule.add_system(SystemConfig::new(c).label("c"));
+
+    assert_eq!(Ok(()), schedule.build());
+    assert_eq!(vec!["b", "c", "a"], run(&mut schedule));
+}
+
+#[test]
+fn test_run_if_and_sync() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).run_if(never));
+    schedule.add_system(SystemConfig::new(b));
+
+    let mut log = Log::default();
+    schedule.run(&mut Services::new(), &mut AppState::new(), &mut log, sync);
+    assert_eq!(vec!["b", "sync"], log.entries);
+}
+
+#[test]
+fn test_system_change_windows() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a));
+    schedule.add_system(SystemConfig::new(b).run_if(|_, log: &Log| log.tick > 2));
+
+    let mut log = Log::default();
+    let mut state = AppState::new();
+    schedule.run(&mut Services::new(), &mut state, &mut log, |_| {});
+    schedule.run(&mut Services::new(), &mut state, &mut log, |_| {});
+    schedule.run(&mut Services::new(), &mut state, &mut log, |_| {});
+
+    // a skipped system keeps its window open until it runs
+    assert_eq!(vec!["a", "a", "a", "b"], log.entries);
+    assert_eq!(vec![0, 1, 2, 0], log.windows);
+}
+
+#[test]
+fn test_cycle() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("a").after("b"));
+    schedule.add_system(SystemConfig::new(b).label("b").after("a"));
+    schedule.add_system(SystemConfig::new(c).in_stage(Stage::Render));
+
+    assert_eq!(Err(ScheduleError::Cycle(Stage::Update)), schedule.build());
+}
+
+#[test]
+fn test_unknown_label() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("a"));
+    schedule.add_system(SystemConfig::new(b).before("render"));
+
+    assert_eq!(Err(ScheduleError::UnknownLabel("render")), schedule.build());
+}
+
+#[test]
+fn test_cross_stage() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("render").in_stage(Stage::Render));
+    schedule.add_system(SystemConfig::new(b).after("render"));
+    assert_eq!(Err(ScheduleError::CrossStage("render")), schedule.build());
+
+    // the stages already run in the requested order
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("render").in_stage(Stage::Render));
+    schedule.add_system(SystemConfig::new(b).before("render"));
+    schedule.add_system(SystemConfig::new(c).in_stage(Stage::Render).after("render"));
+    assert_eq!(Ok(()), schedule.build());
+    assert_eq!(vec!["b", "a", "c"], run(&mut schedule));
+}
+
+#[test]
+#[should_panic(expected = "cycle")]
+fn test_run_panics_on_cycle() {
+    let mut schedule = Schedule::new();
+    schedule.add_system(SystemConfig::new(a).label("a").before("b"));
+    schedule.add_system(SystemConfig::new(b).label("b").before("a"));
+    run(&mut schedule);
+}
@@ -0,0 +1,80 @@
+use engine::components::Transform;
+use math::{UnitQuaternion, Vector3d, Vector3f};
+
+fn assert_near(expected: Vector3d, actual: Vector3d) {
+    assert!((expected - actual).norm() < 1e-4, "expected {:?}, got {:?}", expected, actual);
+}
+
+#[test]
+fn test_euler_round_trip() {
+    let mut transform = Transform::default();
+    transform.set_euler_angles(Vector3f::new(30.0, 45.0, 60.0));
+
+    let angles = transform.euler_angles();
+    assert!((angles - Vector3f::new(30.0, 45.0, 60.0)).norm() < 1e-3);
+}
+
+#[test]
+fn test_look_at() {
+    let mut transform = Transform::default();
+    transform.set_position(Vector3d::new(1.0, 0.0, 0.0));
+    transform.look_at(Vector3d::new(1.0, 0.0, 10.0), Vector3d::y());
+
+    assert_near(Vector3d::z(), transform.front());
+    assert_near(Vector3d::y(), transform.up());
+    assert_near(-Vector3d::x(), transform.right());
+
+    // a target straight above leaves the rotation alone
+    let rotation = transform.rotation();
+    transform.look_at(Vector3d::new(1.0, 5.0, 0.0), Vector3d::y());
+    assert_eq!(rotation, transform.rotation());
+}
+
+#[test]
+fn test_rotate() {
+    let mut transform = Transform::default();
+    transform.rotate(Vector3f::y(), 90.0);
+    assert_near(-Vector3d::x(), transform.front());
+
+    let mut orbit = Transform::default();
+    orbit.set_position(Vector3d::new(1.0, 0.0, 0.0));
+    orbit.rotate_around(Vector3d::zeros(), Vector3f::y(), 90.0);
+    assert_near(Vector3d::new(0.0, 0.0, -1.0), orbit.position());
+    assert_near(-Vector3d::x(), orbit.front());
+
+    // halfway to a quarter turn
+    let mut half = Transform::default();
+    half.slerp(&UnitQuaternion::from_scaled_axis(Vector3f::y() * 90.0f32.to_radians()), 0.5);
+    assert!((half.euler_angles() - Vector3f::new(0.0, 45.0, 0.0)).norm() < 1e-3);
+}
+
+#[test]
+fn test_transform_point() {
+    let mut transform = Transform::default();
+    transform.set_position(Vector3d::new(1.0e7, -2.0, 3.0));
+    transform.set_euler_angles(Vector3f::new(0.0, 90.0, 0.0));
+    transform.set_scale(Vector3f::new(2.0, 1.0, 1.0));
+
+    let point = transform.transform_point(Vector3d::new(1.0, 0.0, 0.0));
+    assert_near(Vector3d::new(1.0e7, -2.0, 1.0), point);
+    assert_near(Vector3d::new(1.0, 0.0, 0.0), transform.inverse_transform_point(point));
+
+    let local = Vector3d::new(0.5, -4.0, 2.5);
+    assert_near(local, transform.inverse_transform_point(transform.transform_point(local)));
+    let matrix = transform.local_matrix_d();
+    assert_near(transform.transform_point(local), matrix.transform_point(&local.into()).coords);
+
+    // a flattened axis maps to 0 instead of dividing by zero
+    transform.set_scale(Vector3f::new(2.0, 0.0, 1.0));
+    let point = transform.inverse_transform_point(Vector3d::new(1.0e7, 3.0, 3.0));
+    assert_near(Vector3d::new(0.0, 0.0, 0.0), point);
+}
+
+#[test]
+fn test_translate_local() {
+    let mut transform = Transform::default();
+    transform.set_euler_angles(Vector3f::new(0.0, 90.0, 0.0));
+    transform.translate_local(Vector3d::new(0.0, 0.0, -2.0));
+    assert_near(Vector3d::new(-2.0, 0.0, 0.0), transform.position());
+    assert_near(-Vector3d::x(), transform.transform_direction(Vector3d::new(0.0, 0.0, -1.0)));
+}
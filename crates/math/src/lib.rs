@@ -4,7 +4,10 @@ pub type Vector3f = nalgebra::Vector3<f32>;
 pub type Vector2f = nalgebra::Vector2<f32>;
 pub type Vector3d = nalgebra::Vector3<f64>;
 pub type Matrix4x4 = nalgebra::Matrix4<f32>;
+pub type Matrix3d = nalgebra::Matrix3<f64>;
+pub type Matrix4d = nalgebra::Matrix4<f64>;
 pub type Rotation3 = nalgebra::Rotation3<f32>;
+pub type UnitQuaternion = nalgebra::UnitQuaternion<f32>;
 pub type Color = nalgebra::Vector3<f32>;
 pub type Point3 = nalgebra::Point3<f32>;
 
@@ -2,8 +2,8 @@ use std::{collections::HashMap, rc::Rc};
 
 use math::{Vector2f, Vector3f};
 use web_sys::{
-    WebGl2RenderingContext, WebGlBuffer, WebGlProgram, WebGlShader, WebGlTexture,
-    WebGlUniformLocation, WebGlVertexArrayObject,
+    WebGl2RenderingContext, WebGlBuffer, WebGlFramebuffer, WebGlProgram, WebGlRenderbuffer,
+    WebGlShader, WebGlTexture, WebGlUniformLocation, WebGlVertexArrayObject,
 };
 
 pub enum DrawMode {
@@ -60,6 +60,68 @@ impl TextureHandle {
 
 use utils::log;
 
+// deleted with the last `Rc`, so a material sampling a render target keeps it alive
+pub struct OwnedTexture {
+    context: Rc<WebGl2RenderingContext>,
+    texture: WebGlTexture,
+}
+
+impl AsRef<WebGlTexture> for OwnedTexture {
+    fn as_ref(&self) -> &WebGlTexture {
+        &self.texture
+    }
+}
+
+impl Drop for OwnedTexture {
+    fn drop(&mut self) {
+        self.context.delete_texture(Some(&self.texture));
+    }
+}
+
+// offscreen color and depth textures, with multisampled renderbuffers resolved into them
+pub struct RenderTarget {
+    context: Rc<WebGl2RenderingContext>,
+    framebuffer: WebGlFramebuffer,
+    color: Rc<OwnedTexture>,
+    depth: Rc<OwnedTexture>,
+    multisample: Option<(WebGlFramebuffer, WebGlRenderbuffer, WebGlRenderbuffer)>,
+    width: i32,
+    height: i32,
+}
+
+impl RenderTarget {
+    // sample this in a material once the target is resolved
+    pub fn texture(&self) -> Rc<OwnedTexture> {
+        Rc::clone(&self.color)
+    }
+
+    pub fn depth_texture(&self) -> Rc<OwnedTexture> {
+        Rc::clone(&self.depth)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+}
+
+// freed with the last `Rc` to the target, e.g. once its camera is gone. The textures go
+// with their own last `Rc`
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        if let Some((framebuffer, color, depth)) = &self.multisample {
+            self.context.delete_renderbuffer(Some(color));
+            self.context.delete_renderbuffer(Some(depth));
+            self.context.delete_framebuffer(Some(framebuffer));
+        }
+        self.context.delete_framebuffer(Some(&self.framebuffer));
+    }
+}
+
 pub struct WebGlRenderer {
     context: Rc<WebGl2RenderingContext>,
 }
@@ -99,6 +161,21 @@ impl WebGlRenderer {
         self.context.viewport(0, 0, width, height)
     }
 
+    pub fn set_viewport_rect(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.context.viewport(x, y, width, height)
+    }
+
+    // limits clears and draws to the rect, `None` turns the scissor test off
+    pub fn set_scissor(&self, rect: Option<(i32, i32, i32, i32)>) {
+        match rect {
+            Some((x, y, width, height)) => {
+                self.context.enable(WebGl2RenderingContext::SCISSOR_TEST);
+                self.context.scissor(x, y, width, height);
+            }
+            None => self.context.disable(WebGl2RenderingContext::SCISSOR_TEST),
+        }
+    }
+
     pub fn create_program(&self, vertex_src: &str, fragment_src: &str) -> ShaderProgramHandle {
         let vertex_shader = self
             .context
@@ -339,6 +416,210 @@ impl WebGlRenderer {
         }
     }
 
+    // `samples` above 1 renders into multisampled renderbuffers, `resolve_render_target` copies them to the textures.
+    // It is clamped to what the device supports, an incomplete framebuffer is an error
+    pub fn create_render_target(&self, width: i32, height: i32, samples: i32) -> Result<RenderTarget, String> {
+        let max_samples = self
+            .context
+            .get_parameter(WebGl2RenderingContext::MAX_SAMPLES)
+            .ok()
+            .and_then(|value| value.as_f64())
+            .map_or(1, |value| value as i32);
+        let samples = samples.min(max_samples);
+
+        let color = self.create_target_texture(
+            width,
+            height,
+            WebGl2RenderingContext::RGBA8,
+            WebGl2RenderingContext::RGBA,
+            WebGl2RenderingContext::UNSIGNED_BYTE,
+            WebGl2RenderingContext::LINEAR,
+        );
+        let depth = self.create_target_texture(
+            width,
+            height,
+            WebGl2RenderingContext::DEPTH_COMPONENT24,
+            WebGl2RenderingContext::DEPTH_COMPONENT,
+            WebGl2RenderingContext::UNSIGNED_INT,
+            WebGl2RenderingContext::NEAREST,
+        );
+
+        let framebuffer = self.context.create_framebuffer().unwrap();
+        self.context
+            .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, Some(&framebuffer));
+        self.context.framebuffer_texture_2d(
+            WebGl2RenderingContext::FRAMEBUFFER,
+            WebGl2RenderingContext::COLOR_ATTACHMENT0,
+            WebGl2RenderingContext::TEXTURE_2D,
+            Some(&color),
+            0,
+        );
+        self.context.framebuffer_texture_2d(
+            WebGl2RenderingContext::FRAMEBUFFER,
+            WebGl2RenderingContext::DEPTH_ATTACHMENT,
+            WebGl2RenderingContext::TEXTURE_2D,
+            Some(&depth),
+            0,
+        );
+
+        // on errors dropping the target frees what was created so far
+        let mut target = RenderTarget {
+            context: Rc::clone(&self.context),
+            framebuffer,
+            color: Rc::new(OwnedTexture {
+                context: Rc::clone(&self.context),
+                texture: color,
+            }),
+            depth: Rc::new(OwnedTexture {
+                context: Rc::clone(&self.context),
+                texture: depth,
+            }),
+            multisample: None,
+            width,
+            height,
+        };
+        let mut status = self.check_framebuffer();
+
+        if status.is_ok() && samples > 1 {
+            let framebuffer = self.context.create_framebuffer().unwrap();
+            self.context
+                .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, Some(&framebuffer));
+            let color = self.create_multisample_renderbuffer(
+                width,
+                height,
+                samples,
+                WebGl2RenderingContext::RGBA8,
+                WebGl2RenderingContext::COLOR_ATTACHMENT0,
+            );
+            let depth = self.create_multisample_renderbuffer(
+                width,
+                height,
+                samples,
+                WebGl2RenderingContext::DEPTH_COMPONENT24,
+                WebGl2RenderingContext::DEPTH_ATTACHMENT,
+            );
+            target.multisample = Some((framebuffer, color, depth));
+            status = self.check_framebuffer();
+        }
+
+        self.context
+            .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, None);
+        status.map(|_| target)
+    }
+
+    fn create_target_texture(
+        &self,
+        width: i32,
+        height: i32,
+        internal_format: u32,
+        format: u32,
+        type_: u32,
+        filter: u32,
+    ) -> WebGlTexture {
+        let texture = self.context.create_texture().unwrap();
+        self.context
+            .bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&texture));
+        for (param, value) in [
+            (WebGl2RenderingContext::TEXTURE_MIN_FILTER, filter),
+            (WebGl2RenderingContext::TEXTURE_MAG_FILTER, filter),
+            (WebGl2RenderingContext::TEXTURE_WRAP_S, WebGl2RenderingContext::CLAMP_TO_EDGE),
+            (WebGl2RenderingContext::TEXTURE_WRAP_T, WebGl2RenderingContext::CLAMP_TO_EDGE),
+        ] {
+            self.context
+                .tex_parameteri(WebGl2RenderingContext::TEXTURE_2D, param, value as i32);
+        }
+
+        self.context
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                WebGl2RenderingContext::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                width,
+                height,
+                0,
+                format,
+                type_,
+                None,
+            )
+            .unwrap();
+        self.context
+            .bind_texture(WebGl2RenderingContext::TEXTURE_2D, None);
+        texture
+    }
+
+    fn create_multisample_renderbuffer(
+        &self,
+        width: i32,
+        height: i32,
+        samples: i32,
+        format: u32,
+        attachment: u32,
+    ) -> WebGlRenderbuffer {
+        let renderbuffer = self.context.create_renderbuffer().unwrap();
+        self.context
+            .bind_renderbuffer(WebGl2RenderingContext::RENDERBUFFER, Some(&renderbuffer));
+        self.context.renderbuffer_storage_multisample(
+            WebGl2RenderingContext::RENDERBUFFER,
+            samples,
+            format,
+            width,
+            height,
+        );
+        self.context.framebuffer_renderbuffer(
+            WebGl2RenderingContext::FRAMEBUFFER,
+            attachment,
+            WebGl2RenderingContext::RENDERBUFFER,
+            Some(&renderbuffer),
+        );
+        renderbuffer
+    }
+
+    fn check_framebuffer(&self) -> Result<(), String> {
+        let status = self
+            .context
+            .check_framebuffer_status(WebGl2RenderingContext::FRAMEBUFFER);
+        if status != WebGl2RenderingContext::FRAMEBUFFER_COMPLETE {
+            return Err(format!("framebuffer incomplete: {:#x}", status));
+        }
+        Ok(())
+    }
+
+    // `None` draws to the canvas again
+    pub fn bind_render_target(&self, target: Option<&RenderTarget>) {
+        let framebuffer = target.map(|target| match &target.multisample {
+            Some((framebuffer, _, _)) => framebuffer,
+            None => &target.framebuffer,
+        });
+        self.context
+            .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, framebuffer);
+    }
+
+    // copies the multisampled buffers into the textures, nothing to do without MSAA
+    pub fn resolve_render_target(&self, target: &RenderTarget) {
+        if let Some((framebuffer, _, _)) = &target.multisample {
+            self.context
+                .bind_framebuffer(WebGl2RenderingContext::READ_FRAMEBUFFER, Some(framebuffer));
+            self.context.bind_framebuffer(
+                WebGl2RenderingContext::DRAW_FRAMEBUFFER,
+                Some(&target.framebuffer),
+            );
+            self.context.blit_framebuffer(
+                0,
+                0,
+                target.width,
+                target.height,
+                0,
+                0,
+                target.width,
+                target.height,
+                WebGl2RenderingContext::COLOR_BUFFER_BIT | WebGl2RenderingContext::DEPTH_BUFFER_BIT,
+                WebGl2RenderingContext::NEAREST,
+            );
+            self.context
+                .bind_framebuffer(WebGl2RenderingContext::FRAMEBUFFER, None);
+        }
+    }
+
     pub fn create_cube_texture(&self, data: Vec<&[u8]>, width: i32, height: i32) -> TextureHandle {
         let texture = self.context.create_texture().unwrap();
         self.context
@@ -435,6 +716,10 @@ impl WebGlRenderer {
         );
     }
 
+    pub fn clear_depth(&self) {
+        self.context.clear(WebGl2RenderingContext::DEPTH_BUFFER_BIT);
+    }
+
     pub fn set_culling(&self, value: bool) {
         if value {
             self.context.enable(WebGl2RenderingContext::CULL_FACE);
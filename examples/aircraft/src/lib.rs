@@ -1,16 +1,14 @@
 use std::rc::Rc;
 
-use component::component;
 use loader::tower_app;
 
-use ecs::Component;
+use ecs::{Component, EventReader, Name};
 use engine::components::{Camera, Mesh, Transform};
 use engine::init_world;
 use engine::types::{Material, MeshData, Texture, Tlu};
-use math::{Color, Vector3d, Vector3f};
+use math::{to_single_vector, Color, Vector3d, Vector3f};
 use webapp::app::{AppState, Window};
 
-#[component]
 struct CameraInput {
     x_rel: i32,
     y_rel: i32,
@@ -18,10 +16,10 @@ struct CameraInput {
     right: i32,
 }
 
-#[component]
-struct Aircraft {}
+#[derive(Clone, Copy, Default)]
+struct Aircraft;
 
-init_world! {CameraInput, Aircraft}
+init_world! {; tags: Aircraft}
 
 #[tower_app]
 async fn start() -> Result<(), JsValue> {
@@ -62,6 +60,10 @@ async fn start() -> Result<(), JsValue> {
     //     utils::log(format!("{:?}", w.stat()).as_str());
     // });
 
+    engine
+        .build()
+        .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
     Window::new(engine);
     Ok(())
 }
@@ -155,8 +157,7 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
             .texture(),
     );
 
-    for e in world.query().scenedata().fetch() {
-        let scene_data = world.get_scenedata(e).unwrap();
+    if let Some(scene_data) = world.resource_mut::<SceneData>() {
         scene_data.ambient = Color::new(0.1, 0.1, 0.1);
         scene_data.env = Some(Rc::clone(&skybox_texture));
     }
@@ -170,7 +171,7 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
 
     let mut transform = Transform::new(camera_entity);
     transform.set_position(Vector3d::new(-3.0, 4.5, -11.0));
-    transform.set_euler_angles(Vector3f::new(-25.0f32, 80.0f32, 0.0f32));
+    transform.look_at(Vector3d::zeros(), Vector3d::y());
     transform.add(world);
 
     let aircraft_entity = world.create_entity();
@@ -196,11 +197,11 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
     transform.set_position(Vector3d::new(0.0, 0.0, 0.0));
     transform.add(world);
 
-    Aircraft {
-        entity: aircraft_entity,
-        one_frame: false,
-    }
-    .add(world);
+    world.add_tag(aircraft_entity, Aircraft);
+
+    let mut name = Name::new("aircraft");
+    name.entity = aircraft_entity;
+    name.add(world);
 
     // -- lights
 
@@ -252,64 +253,44 @@ fn process_input(services: &mut Services, state: &mut AppState, world: &mut Worl
 
     let mouse_delta = state.get_mouse_delta();
 
-    CameraInput {
-        entity: world.create_entity(),
-        one_frame: true,
+    world.send_event(CameraInput {
         x_rel: mouse_delta.0,
         y_rel: mouse_delta.1,
         forward,
         right,
-    }
-    .add(world);
+    });
 }
 
 const MOUSE_SENSITIVITY: f32 = 0.1;
 const MOVEMENT_SPEED: f64 = 5.0;
 
 fn rotate_camera(services: &mut Services, state: &mut AppState, world: &mut World) {
-    for e in world.query().camerainput().fetch() {
-        let input = world.get_camerainput(e).unwrap();
-        for e in world.query().camera().transform().fetch() {
-            let transform = world.get_transform(e).unwrap();
-
-            transform.set_euler_angles(
-                transform.euler_angles()
-                    + Vector3f::new(
-                        -input.y_rel as f32 * MOUSE_SENSITIVITY,
-                        input.x_rel as f32 * MOUSE_SENSITIVITY,
-                        0.0f32,
-                    ),
-            );
-
-            if transform.euler_angles().x > 89.0 {
-                let mut angles = transform.euler_angles();
-                angles.x = 89.0;
-                transform.set_euler_angles(angles);
-            } else if transform.euler_angles().x < -89.0 {
-                let mut angles = transform.euler_angles();
-                angles.x = -89.0;
-                transform.set_euler_angles(angles);
-            }
-
-            transform.set_position(
-                transform.position()
-                    + transform
-                        .front()
-                        .scale(input.forward as f64 * MOVEMENT_SPEED * state.delta_time() as f64),
-            );
-            transform.set_position(
-                transform.position()
-                    + transform
-                        .right()
-                        .scale(input.right as f64 * MOVEMENT_SPEED * state.delta_time() as f64),
-            );
+    let mut reader = world
+        .remove_resource::<EventReader<CameraInput>>()
+        .unwrap_or_default();
+    let inputs: Vec<_> = world
+        .read_events(&mut reader)
+        .map(|input| (input.x_rel, input.y_rel, input.forward, input.right))
+        .collect();
+    world.insert_resource(reader);
+
+    for (x_rel, y_rel, forward, right) in inputs {
+        for (transform, _) in world.query_mut::<(&mut Transform, &Camera)>() {
+            transform.rotate(Vector3f::y(), -x_rel as f32 * MOUSE_SENSITIVITY);
+
+            // pitch around the camera's own right axis, short of straight up or down
+            let pitch = transform.front().y.asin().to_degrees() as f32;
+            let target = (pitch - y_rel as f32 * MOUSE_SENSITIVITY).clamp(-89.0, 89.0);
+            transform.rotate(to_single_vector(transform.right()), target - pitch);
+
+            let step = MOVEMENT_SPEED * state.delta_time() as f64;
+            transform.translate_local(Vector3d::new(right as f64 * step, 0.0, -forward as f64 * step));
         }
     }
 }
 
 fn move_plane_lissajous(services: &mut Services, state: &mut AppState, world: &mut World) {
-    for e in world.query().aircraft().transform().fetch() {
-        let transform = world.get_transform(e).unwrap();
+    for (transform, _) in world.query_mut::<(&mut Transform, &Aircraft)>() {
 
         let a1 = 0.5f32;
         let b1 = 0.5f32;
@@ -326,6 +307,6 @@ fn move_plane_lissajous(services: &mut Services, state: &mut AppState, world: &m
 
         let angle = (state.time() * 0.0002).sin() * 0.25;
 
-        transform.set_euler_angles(Vector3f::new(0.0, 0.0, angle as f32));
+        transform.set_euler_angles(Vector3f::new(0.0, 0.0, (angle as f32).to_degrees()));
     }
 }
@@ -3,8 +3,8 @@ use std::rc::Rc;
 use component::component;
 use loader::tower_app;
 
-use ecs::Component;
-use engine::components::{Camera, Mesh, Transform};
+use ecs::{Component, Entity};
+use engine::components::{Camera, Mesh, MeshBundle, Transform};
 use engine::init_world;
 use engine::types::{Material, MeshData, Texture, Tlu};
 use math::{Color, Vector3d, Vector3f};
@@ -43,6 +43,10 @@ async fn start() -> Result<(), JsValue> {
     engine.register_init(init_scene);
     engine.register_tick(rotate_cube);
 
+    engine
+        .build()
+        .map_err(|error| JsValue::from_str(&error.to_string()))?;
+
     Window::new(engine);
     Ok(())
 }
@@ -89,24 +93,17 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
         )
         .vao();
 
-    for e in world.query().scenedata().fetch() {
-        let scene_data = world.get_scenedata(e).unwrap();
+    if let Some(scene_data) = world.resource_mut::<SceneData>() {
         scene_data.ambient = Color::new(0.1, 0.1, 0.1);
     }
 
     // components
 
-    let camera_entity = world.create_entity();
-    let camera = Camera::new(camera_entity);
-
-    camera.add(world);
-
-    let mut transform = Transform::new(camera_entity);
+    let mut transform = Transform::default();
     transform.set_position(Vector3d::new(0.0, 1.8, 3.0));
-    transform.set_euler_angles(Vector3f::new(-25.0, -90.0, 0.0));
-    transform.add(world);
+    transform.set_euler_angles(Vector3f::new(-25.0, 0.0, 0.0));
 
-    let cube_entity = world.create_entity();
+    world.spawn().with(Camera::default()).with(transform);
 
     let material = Rc::new(Material::new(
         shader_lit,
@@ -116,26 +113,21 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
         Some(texture_spec_id),
     ));
 
-    Mesh {
-        entity: cube_entity,
-        one_frame: false,
-        mesh_data: mesh_data,
-        mesh_id,
-        material: Some(Rc::clone(&material)),
-    }
-    .add(world);
-
-    let mut transform = Transform::new(cube_entity);
+    let mut transform = Transform::default();
     transform.set_position(Vector3d::new(0.0, 0.0, 0.0));
     transform.set_scale(Vector3f::new(2.0, 1.0, 2.0));
-    transform.add(world);
 
-    Cube {
-        entity: cube_entity,
-        one_frame: false,
-        spin_speed: 45f32,
-    }
-    .add(world);
+    world
+        .spawn()
+        .with(MeshBundle {
+            transform,
+            mesh: Mesh::new(mesh_data, mesh_id, Some(Rc::clone(&material))),
+        })
+        .with(Cube {
+            entity: Entity::default(),
+            one_frame: false,
+            spin_speed: 45f32,
+        });
 
     // -- lights
 
@@ -158,16 +150,7 @@ fn init_scene(services: &mut Services, state: &mut AppState, world: &mut World)
 }
 
 fn rotate_cube(services: &mut Services, state: &mut AppState, world: &mut World) {
-    for e in world.query().mesh().transform().cube().fetch() {
-        let transform = world.get_transform(e).unwrap();
-        let cube = world.get_cube(e).unwrap();
-        transform.set_euler_angles(
-            transform.euler_angles()
-                + Vector3f::new(
-                    0.0f32,
-                    -cube.spin_speed.to_radians() * state.delta_time(),
-                    0.0f32,
-                ),
-        );
+    for (transform, cube, _) in world.query_mut::<(&mut Transform, &Cube, &Mesh)>() {
+        transform.rotate(Vector3f::y(), -cube.spin_speed * state.delta_time());
     }
 }